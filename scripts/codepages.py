@@ -0,0 +1,98 @@
+"""Generate the legacy code page tables in `src/melt/ttf/names/codepages/`,
+used to decode `name` records in non-Unicode encodings.
+
+Usage: python3 scripts/codepages.py <path to a copy of MAPPINGS>
+
+The directory must be a copy of <https://www.unicode.org/Public/MAPPINGS/>,
+at least of the files listed in `SOURCES`.
+"""
+
+import sys
+from pathlib import Path
+
+OUTPUT = (
+    Path(__file__).resolve().parent.parent
+    / "src" / "melt" / "ttf" / "names" / "codepages"
+)
+
+SOURCES = {
+    "windows_shift_jis.txt": "VENDORS/MICSFT/WINDOWS/CP932.TXT",
+    "windows_prc.txt": "VENDORS/MICSFT/WINDOWS/CP936.TXT",
+    "windows_big5.txt": "VENDORS/MICSFT/WINDOWS/CP950.TXT",
+    "windows_wansung.txt": "VENDORS/MICSFT/WINDOWS/CP949.TXT",
+    "windows_johab.txt": "OBSOLETE/EASTASIA/KSC/JOHAB.TXT",
+    "mac_japanese.txt": "VENDORS/APPLE/JAPANESE.TXT",
+    "mac_chinese_traditional.txt": "VENDORS/APPLE/CHINTRAD.TXT",
+    "mac_korean.txt": "VENDORS/APPLE/KOREAN.TXT",
+    "mac_arabic.txt": "VENDORS/APPLE/ARABIC.TXT",
+    "mac_hebrew.txt": "VENDORS/APPLE/HEBREW.TXT",
+    "mac_greek.txt": "VENDORS/APPLE/GREEK.TXT",
+    "mac_russian.txt": "VENDORS/APPLE/CYRILLIC.TXT",
+    "mac_thai.txt": "VENDORS/APPLE/THAI.TXT",
+    "mac_chinese_simplified.txt": "VENDORS/APPLE/CHINSIMP.TXT",
+    "mac_slavic.txt": "VENDORS/APPLE/CENTEURO.TXT",
+    "mac_devanagari.txt": "VENDORS/APPLE/DEVANAGA.TXT",
+    "mac_gurmukhi.txt": "VENDORS/APPLE/GURMUKHI.TXT",
+    "mac_gujarati.txt": "VENDORS/APPLE/GUJARATI.TXT",
+    "mac_symbol.txt": "VENDORS/APPLE/SYMBOL.TXT",
+}
+
+
+def unicode(field):
+    """Characters of `0xXXXX` or `0xXXXX+0xYYYY`, `None` for Apple's
+    direction hints like `<RL>+0x0020`."""
+    try:
+        return "".join(chr(int(code, 16)) for code in field.split("+"))
+    except ValueError:
+        return None
+
+
+def mappings(path):
+    """Byte sequences as integers mapped to characters, ASCII left out."""
+    result = {}
+    for line in path.read_text(encoding="latin-1").splitlines():
+        fields = line.split("#", 1)[0].split()
+        if len(fields) < 2:
+            continue
+        # Apple writes byte sequences like `0xA1+0xE9`
+        code = int(fields[0].replace("+0x", ""), 16)
+        text = unicode(fields[1])
+        if text is None or (code < 0x80 and text == chr(code)):
+            continue
+        result[code] = text
+    return result
+
+
+def lines(table):
+    """Runs of consecutive codes mapped to one character each as
+    `start;characters`, longer mappings alone as `code+;characters`."""
+    run_start, run = None, ""
+    for code in sorted(table):
+        text = table[code]
+        if len(text) > 1:
+            if run:
+                yield f"{run_start:X};{run}"
+                run_start, run = None, ""
+            yield f"{code:X}+;{text}"
+            continue
+        if run and run_start + len(run) == code:
+            run += text
+        else:
+            if run:
+                yield f"{run_start:X};{run}"
+            run_start, run = code, text
+    if run:
+        yield f"{run_start:X};{run}"
+
+
+def main(root):
+    root = Path(root)
+    OUTPUT.mkdir(parents=True, exist_ok=True)
+    for name, source in SOURCES.items():
+        header = [f"# Generated by scripts/codepages.py from {source}."]
+        text = "\n".join(header + list(lines(mappings(root / source)))) + "\n"
+        (OUTPUT / name).write_text(text, encoding="utf-8")
+
+
+if __name__ == "__main__":
+    main(sys.argv[1])
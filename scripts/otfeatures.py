@@ -0,0 +1,202 @@
+"""Generate the OpenType feature tag tables in `src/melt/ttf/features/registry/`.
+
+Usage: python3 scripts/otfeatures.py <featurelist.html>
+
+The page is a saved copy of the OpenType feature tag registry,
+<https://learn.microsoft.com/en-us/typography/opentype/spec/featurelist>.
+Categories, and which features shapers apply by default, are not part of the
+registry and are kept below, following HarfBuzz's shapers.
+"""
+
+import re
+import sys
+from html.parser import HTMLParser
+from pathlib import Path
+
+OUTPUT = (
+    Path(__file__).resolve().parent.parent
+    / "src" / "melt" / "ttf" / "features" / "registry"
+)
+
+CATEGORIES = {
+    "ligatures": "liga clig dlig hlig rlig",
+    "numerals": "lnum onum pnum tnum frac afrc numr dnom zero",
+    "superscripts": "sups subs sinf ordn",
+    "case": "smcp c2sc pcap c2pc unic case cpsp titl",
+    "positional-forms": "init medi med2 fina fin2 fin3 isol",
+    "cjk": (
+        "jp78 jp83 jp90 jp04 hojo nlck trad smpl tnam expt fwid hwid halt "
+        "pwid palt qwid twid hkna pkna ruby nalt cpct chws apkn hngl ljmo "
+        "vjmo tjmo"
+    ),
+    "vertical": "vert vrt2 vrtr valt vhal vpal vapk vkrn vkna vchw",
+    "math": "ssty mgrk dtls flac",
+    "positioning": "kern mark mkmk curs dist abvm blwm opbd lfbd rtbd",
+    "script-shaping": (
+        "nukt akhn rphf rkrf pref blwf abvf half pstf vatu cjct cfar pres "
+        "abvs blws psts haln stch mset"
+    ),
+    "alternates": (
+        "aalt calt rclt salt swsh cswh hist jalt falt rand ornm ital "
+        "ss01-ss20 cv01-cv99"
+    ),
+    "general": "ccmp locl rvrn ltra ltrm rtla rtlm size",
+}
+
+# how shapers apply features, those left out are off unless users enable them
+DEFAULTS = {
+    # in any direction and script
+    "always": "abvm blwm ccmp locl mark mkmk rlig rvrn rand",
+    "horizontal": "calt clig curs dist kern liga rclt",
+    "vertical": "vert vkrn",
+    "left-to-right": "ltra ltrm",
+    "right-to-left": "rtla rtlm",
+    # around U+2044 FRACTION SLASH
+    "fractions": "frac numr dnom",
+}
+
+# shapers applying script specific features, see `ot_shaper_*.rs` of HarfBuzz
+SHAPERS = {
+    "arabic": "Arab Syrc",
+    "syriac": "Syrc",
+    "indic": "Beng Deva Gujr Guru Knda Mlym Orya Taml Telu",
+    "khmer": "Khmr",
+    "myanmar": "Mymr",
+    "hangul": "Hang",
+    # the Universal Shaping Engine
+    "use": (
+        "Adlm Ahom Bali Batk Bhks Brah Bugi Buhd Cakm Cham Chrs Cpmn Diak "
+        "Dogr Dupl Egyp Elym Gara Gong Gonm Gran Gukh Hano Hmng Hmnp Java "
+        "Kali Kawi Khar Khoj Kits Krai Kthi Lana Lepc Limb Mahj Maka Mand "
+        "Mani Marc Medf Modi Mong Mtei Mult Nagm Nand Newa Nkoo Onao Ougr "
+        "Phag Phlp Plrd Rjng Rohg Saur Shrd Sidd Sind Sinh Sogd Sogo Soyo "
+        "Sund Sunu Sylo Tagb Takr Tale Tavt Tfng Tglg Tibt Tirh Tnsa Todr "
+        "Toto Tutg Vith Wcho Yezi Zanb"
+    ),
+}
+
+SCRIPT_FEATURES = {
+    "isol": "arabic use",
+    "init": "arabic indic use",
+    "medi": "arabic use",
+    "fina": "arabic use",
+    "med2": "syriac",
+    "fin2": "syriac",
+    "fin3": "syriac",
+    "stch": "syriac",
+    "mset": "arabic",
+    "nukt": "indic use",
+    "akhn": "indic use",
+    "rphf": "indic myanmar use",
+    "rkrf": "indic use",
+    "pref": "indic khmer myanmar use",
+    "blwf": "indic khmer myanmar use",
+    "abvf": "indic khmer use",
+    "half": "indic use",
+    "pstf": "indic khmer myanmar use",
+    "vatu": "indic use",
+    "cjct": "indic use",
+    "cfar": "khmer",
+    "pres": "indic khmer myanmar use",
+    "abvs": "indic khmer myanmar use",
+    "blws": "indic khmer myanmar use",
+    "psts": "indic khmer myanmar use",
+    "haln": "indic use",
+    "ljmo": "hangul",
+    "vjmo": "hangul",
+    "tjmo": "hangul",
+}
+
+
+class Rows(HTMLParser):
+    """Text of the cells of each table row."""
+
+    def __init__(self):
+        super().__init__()
+        self.rows, self.row, self.cell = [], None, None
+
+    def handle_starttag(self, tag, attrs):
+        if tag == "tr":
+            self.row = []
+        elif tag in ("td", "th") and self.row is not None:
+            self.cell = ""
+
+    def handle_endtag(self, tag):
+        if tag in ("td", "th") and self.cell is not None:
+            self.row.append(" ".join(self.cell.split()))
+            self.cell = None
+        elif tag == "tr" and self.row is not None:
+            self.rows.append(self.row)
+            self.row = None
+
+    def handle_data(self, data):
+        if self.cell is not None:
+            self.cell += data
+
+
+def expand(tags):
+    """Tags of a field like `ss01-ss20 salt`, ranges expanded."""
+    for tag in tags.split():
+        start, _, end = tag.partition("-")
+        if not end:
+            yield tag
+            continue
+        for number in range(int(start[2:]), int(end[2:]) + 1):
+            yield f"{start[:2]}{number:02}"
+
+
+def lookup(table):
+    return {tag: key for key, tags in table.items() for tag in expand(tags)}
+
+
+def registered(path):
+    """Registered tags with their friendly names, ranges like
+    `'cv01' - 'cv99'` named `Character Variant 1 - Character Variant 99`
+    expanded into one tag per number."""
+    parser = Rows()
+    parser.feed(Path(path).read_text(encoding="utf-8"))
+    for row in parser.rows:
+        if len(row) < 2:
+            continue
+        tags = re.findall(r"'([^']{4})'", row[0])
+        if len(tags) == 1:
+            yield tags[0], row[1]
+        elif len(tags) == 2:
+            name = re.split(r"\s+[-–]\s+", row[1])[0]
+            name = re.sub(r"\s*\d+$", "", name)
+            for tag in expand(f"{tags[0]}-{tags[1]}"):
+                yield tag, f"{name} {int(tag[2:])}"
+
+
+def main(path):
+    categories, defaults = lookup(CATEGORIES), lookup(DEFAULTS)
+    lines = []
+    for tag, name in registered(path):
+        shapers = SCRIPT_FEATURES.get(tag, "")
+        default = defaults.get(tag, "script" if shapers else "off")
+        category = categories.get(tag, "")
+        lines.append(f"{tag};{name};{category};{default};{shapers}")
+    OUTPUT.mkdir(parents=True, exist_ok=True)
+    write(
+        "features.txt",
+        "tag;friendly name;category;default;shapers applying it",
+        lines,
+    )
+    write(
+        "shapers.txt",
+        "shaper;ISO 15924 codes of the scripts it shapes",
+        (f"{shaper};{codes}" for shaper, codes in SHAPERS.items()),
+    )
+
+
+def write(name, description, lines):
+    header = [
+        f"# {description}",
+        "# Generated by scripts/otfeatures.py from the OpenType feature tag registry.",
+    ]
+    text = "\n".join(header + sorted(set(lines))) + "\n"
+    (OUTPUT / name).write_text(text, encoding="utf-8")
+
+
+if __name__ == "__main__":
+    main(sys.argv[1])
@@ -0,0 +1,159 @@
+"""Generate the OpenType script and language system tag tables in
+`src/melt/ttf/scripts/registry/`.
+
+Usage: python3 scripts/ottags.py <scripttags.html> <languagetags.html> <iso-639-3.tab>
+
+The pages are saved copies of the OpenType script and language system tag
+registries, <https://learn.microsoft.com/en-us/typography/opentype/spec/scripttags>
+and <https://learn.microsoft.com/en-us/typography/opentype/spec/languagetags>.
+The code table is published at <https://iso639-3.sil.org/code_tables/download_tables>.
+"""
+
+import re
+import sys
+from html.parser import HTMLParser
+from pathlib import Path
+
+OUTPUT = (
+    Path(__file__).resolve().parent.parent
+    / "src" / "melt" / "ttf" / "scripts" / "registry"
+)
+
+# script tags not derived from their ISO 15924 code by lowercasing it, with
+# the codes they stand for, see also HarfBuzz's `hb-ot-tag.cc`
+SCRIPT_CODES = {
+    "kana": "Hira Kana",
+    "hani": "Hani Hans Hant",
+    "lao ": "Laoo",
+    "yi  ": "Yiii",
+    "nko ": "Nkoo",
+    "vai ": "Vaii",
+    "math": "Zmth",
+    "musc": "Zsym",
+    "byzm": "Zsym",
+    "DFLT": "",
+}
+
+# script tags of the second version of the Indic shaping model, superseding
+# the tags of the first one
+NEW_SCRIPT_TAGS = {
+    "bng2": "beng",
+    "dev2": "deva",
+    "gjr2": "gujr",
+    "gur2": "guru",
+    "knd2": "knda",
+    "mlm2": "mlym",
+    "ory2": "orya",
+    "tml2": "taml",
+    "tel2": "telu",
+    "mym2": "mymr",
+}
+
+
+class Rows(HTMLParser):
+    """Text of the cells of each table row."""
+
+    def __init__(self):
+        super().__init__()
+        self.rows, self.row, self.cell = [], None, None
+
+    def handle_starttag(self, tag, attrs):
+        if tag == "tr":
+            self.row = []
+        elif tag in ("td", "th") and self.row is not None:
+            self.cell = ""
+
+    def handle_endtag(self, tag):
+        if tag in ("td", "th") and self.cell is not None:
+            self.row.append(" ".join(self.cell.split()))
+            self.cell = None
+        elif tag == "tr" and self.row is not None:
+            self.rows.append(self.row)
+            self.row = None
+
+    def handle_data(self, data):
+        if self.cell is not None:
+            self.cell += data
+
+
+def rows(path):
+    parser = Rows()
+    parser.feed(Path(path).read_text(encoding="utf-8"))
+    return parser.rows
+
+
+def tag_of(cell):
+    """The tag of a cell like `'dev2'` or `'ZHS '`, padded to four bytes."""
+    match = re.fullmatch(r"'([^']{1,4})'", cell.strip())
+    return match and match.group(1).ljust(4)
+
+
+def bcp47_codes(path):
+    """ISO 639-3 codes mapped to the shortest ISO 639 code, as BCP 47 uses."""
+    codes = {}
+    lines = Path(path).read_text(encoding="utf-8").splitlines()
+    for line in lines[1:]:
+        fields = line.split("\t")
+        if len(fields) > 3:
+            codes[fields[0]] = fields[3] or fields[0]
+            if fields[1]:
+                codes[fields[1]] = fields[3] or fields[0]
+    return codes
+
+
+def script_lines(path):
+    for row in rows(path):
+        tags = [tag_of(cell) for cell in row]
+        if len(row) < 2 or not any(tags):
+            continue
+        index = next(i for i, tag in enumerate(tags) if tag)
+        tag, name = tags[index], row[1 - index] if index < 2 else row[0]
+        base = NEW_SCRIPT_TAGS.get(tag, tag)
+        codes = SCRIPT_CODES.get(base, base.strip().capitalize())
+        superseded_by = next(
+            (new for new, old in NEW_SCRIPT_TAGS.items() if old == tag), ""
+        )
+        yield f"{tag};{name};{codes};{superseded_by}"
+
+
+def language_lines(path, bcp47):
+    for row in rows(path):
+        if len(row) < 2:
+            continue
+        tag = tag_of(row[1])
+        if not tag:
+            continue
+        name, deprecated = row[0], "0"
+        if "(deprecated)" in name:
+            name, deprecated = name.replace("(deprecated)", "").strip(), "1"
+        iso = [code.strip() for code in (row[2] if len(row) > 2 else "").split(",")]
+        iso = [code for code in iso if code]
+        tags = sorted({bcp47.get(code, code) for code in iso})
+        yield f"{tag};{name};{' '.join(iso)};{' '.join(tags)};{deprecated}"
+
+
+def write(name, description, lines):
+    header = [
+        f"# {description}",
+        "# Generated by scripts/ottags.py from the OpenType tag registries.",
+    ]
+    text = "\n".join(header + sorted(set(lines))) + "\n"
+    (OUTPUT / name).write_text(text, encoding="utf-8")
+
+
+def main(scripts, languages, iso_639_3):
+    OUTPUT.mkdir(parents=True, exist_ok=True)
+    write(
+        "scripts.txt",
+        "tag;name;ISO 15924 codes;tag superseding it",
+        script_lines(scripts),
+    )
+    write(
+        "languages.txt",
+        "tag;name;ISO 639 codes;BCP 47 language subtags;deprecated",
+        language_lines(languages, bcp47_codes(iso_639_3)),
+    )
+
+
+if __name__ == "__main__":
+    main(*sys.argv[1:4])
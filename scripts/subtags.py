@@ -0,0 +1,73 @@
+"""Generate the BCP 47 subtag tables in `src/melt/bcp47/`, used to validate
+language tags, e.g. those of the `meta` table.
+
+Usage: python3 scripts/subtags.py <path to the common directory of CLDR>
+
+The directory must be a copy of `common/` of a Unicode CLDR release, see
+<https://github.com/unicode-org/cldr>, at least of `validity/` and
+`dtd/ldmlSupplemental.dtd`. Its validity data lists the language, script,
+region and variant subtags of the IANA Language Subtag Registry, deprecated
+and private use ones included, save scripts not encoded in Unicode.
+"""
+
+import re
+import sys
+import xml.etree.ElementTree as ET
+from pathlib import Path
+
+OUTPUT = Path(__file__).resolve().parent.parent / "src" / "melt" / "bcp47"
+
+# one output file per type, extended language subtags are language subtags
+# as well
+TYPES = ("language", "script", "region", "variant")
+
+# grandfathered tags, frozen by RFC 5646 and not in the validity data
+GRANDFATHERED = (
+    "art-lojban", "cel-gaulish", "en-GB-oed", "i-ami", "i-bnn", "i-default",
+    "i-enochian", "i-hak", "i-klingon", "i-lux", "i-mingo", "i-navajo",
+    "i-pwn", "i-tao", "i-tay", "i-tsu", "no-bok", "no-nyn", "sgn-BE-FR",
+    "sgn-BE-NL", "sgn-CH-DE", "zh-guoyu", "zh-hakka", "zh-min", "zh-min-nan",
+    "zh-xiang",
+)
+
+
+def version(root):
+    """The CLDR version, e.g. `48`."""
+    dtd = (root / "dtd" / "ldmlSupplemental.dtd").read_text(encoding="utf-8")
+    match = re.search(r'cldrVersion\s+CDATA\s+#FIXED\s+"([^"]+)"', dtd)
+    return match.group(1) if match else "unknown"
+
+
+def subtags(root, kind):
+    """Subtags of every status, CLDR's ranges like `qaa~z` written as
+    `qaa..qaz`."""
+    tree = ET.parse(root / "validity" / f"{kind}.xml")
+    for element in tree.iter("id"):
+        for item in (element.text or "").split():
+            start, _, end = item.partition("~")
+            if end:
+                yield f"{start}..{start[: -len(end)]}{end}"
+            else:
+                yield start
+
+
+def write(kind, lines, source):
+    header = [
+        "# subtag or range of subtags",
+        f"# Generated by scripts/subtags.py from {source}.",
+    ]
+    text = "\n".join(header + sorted(set(lines), key=str.lower)) + "\n"
+    (OUTPUT / f"{kind}.txt").write_text(text, encoding="utf-8")
+
+
+def main(root):
+    root = Path(root)
+    source = f"the validity data of CLDR {version(root)}"
+    OUTPUT.mkdir(parents=True, exist_ok=True)
+    for kind in TYPES:
+        write(kind, subtags(root, kind), source)
+    write("grandfathered", GRANDFATHERED, "RFC 5646")
+
+
+if __name__ == "__main__":
+    main(sys.argv[1])
@@ -0,0 +1,158 @@
+"""Generate the compact Unicode Character Database tables in `src/melt/ucd/`.
+
+Usage: python3 scripts/ucd.py <path to an extracted UCD directory>
+
+The directory must contain `UnicodeData.txt`, `Blocks.txt`, `Scripts.txt`,
+`ScriptExtensions.txt`, `EastAsianWidth.txt` and `PropertyValueAliases.txt`
+as published at <https://www.unicode.org/Public/UCD/latest/ucd/>.
+"""
+
+import re
+import sys
+from pathlib import Path
+
+OUTPUT = Path(__file__).resolve().parent.parent / "src" / "melt" / "ucd"
+
+# names derived from the code point, stored as ranges instead of one by one,
+# `<prefix>-<code point>` or the Hangul syllable name for `HANGUL SYLLABLE`
+DERIVED_NAMES = (
+    "CJK UNIFIED IDEOGRAPH",
+    "CJK COMPATIBILITY IDEOGRAPH",
+    "TANGUT IDEOGRAPH",
+    "KHITAN SMALL SCRIPT CHARACTER",
+    "NUSHU CHARACTER",
+    "EGYPTIAN HIEROGLYPH",
+)
+
+RANGE_NAMES = {
+    "CJK Ideograph": "CJK UNIFIED IDEOGRAPH",
+    "Hangul Syllable": "HANGUL SYLLABLE",
+    "Tangut Ideograph": "TANGUT IDEOGRAPH",
+}
+
+
+def records(path):
+    """Fields of each data line, comments stripped."""
+    for line in path.read_text(encoding="utf-8").splitlines():
+        line = line.split("#", 1)[0].strip()
+        if line:
+            yield [field.strip() for field in line.split(";")]
+
+
+def code_range(text):
+    start, _, end = text.partition("..")
+    return int(start, 16), int(end or start, 16)
+
+
+def merge(entries):
+    """Sorted `(start, end, value)` ranges, adjacent equal values merged."""
+    merged = []
+    for start, end, value in sorted(entries):
+        if merged and merged[-1][1] + 1 == start and merged[-1][2] == value:
+            merged[-1] = (merged[-1][0], end, value)
+        else:
+            merged.append((start, end, value))
+    return merged
+
+
+def write(name, version, description, lines):
+    header = [
+        f"# {description}",
+        f"# Generated by scripts/ucd.py from the Unicode Character Database {version}.",
+    ]
+    text = "\n".join(header + list(lines)) + "\n"
+    (OUTPUT / name).write_text(text, encoding="utf-8")
+
+
+def write_ranges(name, version, description, entries):
+    lines = (f"{start:X};{end:X};{value}" for start, end, value in merge(entries))
+    write(name, version, description, lines)
+
+
+def main(ucd):
+    ucd = Path(ucd)
+    version = re.search(
+        r"Blocks-(\d+\.\d+\.\d+)\.txt",
+        (ucd / "Blocks.txt").read_text(encoding="utf-8"),
+    ).group(1)
+
+    names, derived, categories, classes, bidi = [], [], [], [], []
+    first = None
+    for fields in records(ucd / "UnicodeData.txt"):
+        code, name, category = int(fields[0], 16), fields[1], fields[2]
+        combining, bidi_class = int(fields[3]), fields[4]
+        if name.endswith(", First>"):
+            first = code
+            continue
+        start = code
+        if name.endswith(", Last>"):
+            start = first
+            kind = name[1:-len(", Last>")]
+            if kind in RANGE_NAMES:
+                derived.append((start, code, RANGE_NAMES[kind]))
+        elif not name.startswith("<"):
+            prefix = next((p for p in DERIVED_NAMES if name == f"{p}-{code:X}"), None)
+            if prefix is None:
+                names.append((code, name))
+            else:
+                derived.append((code, code, prefix))
+        categories.append((start, code, category))
+        classes.append((start, code, combining))
+        bidi.append((start, code, bidi_class))
+
+    # front coded: code point delta, length of the prefix shared with the
+    # previous name, rest of the name
+    lines, previous_code, previous_name = [], 0, ""
+    for code, name in names:
+        shared = 0
+        while (
+            shared < min(len(name), len(previous_name))
+            and name[shared] == previous_name[shared]
+        ):
+            shared += 1
+        lines.append(f"{code - previous_code:X};{shared};{name[shared:]}")
+        previous_code, previous_name = code, name
+    write("names.txt", version, "delta;shared prefix length;suffix", lines)
+    write_ranges("derived_names.txt", version, "start;end;prefix", derived)
+    write_ranges("general_category.txt", version, "start;end;gc, Cn if missing", categories)
+    write_ranges(
+        "combining_class.txt",
+        version,
+        "start;end;ccc, 0 if missing",
+        (entry for entry in classes if entry[2] != 0),
+    )
+    write_ranges("bidi_class.txt", version, "start;end;bc, unassigned if missing", bidi)
+
+    scripts = [
+        (*code_range(fields[0]), fields[1]) for fields in records(ucd / "Scripts.txt")
+    ]
+    write_ranges("scripts.txt", version, "start;end;sc, Unknown if missing", scripts)
+
+    long_names = {
+        fields[1]: fields[2]
+        for fields in records(ucd / "PropertyValueAliases.txt")
+        if fields[0] == "sc"
+    }
+    extensions = [
+        (*code_range(fields[0]), " ".join(long_names[s] for s in fields[1].split()))
+        for fields in records(ucd / "ScriptExtensions.txt")
+    ]
+    write_ranges(
+        "script_extensions.txt", version, "start;end;scx, sc if missing", extensions
+    )
+
+    blocks = [
+        (*code_range(fields[0]), fields[1]) for fields in records(ucd / "Blocks.txt")
+    ]
+    write_ranges("blocks.txt", version, "start;end;blk, No_Block if missing", blocks)
+
+    widths = [
+        (*code_range(fields[0]), fields[1])
+        for fields in records(ucd / "EastAsianWidth.txt")
+        if fields[1] != "N"
+    ]
+    write_ranges("east_asian_width.txt", version, "start;end;ea, N if missing", widths)
+
+
+if __name__ == "__main__":
+    main(sys.argv[1])
@@ -2,6 +2,7 @@ mod melt;
 
 mod wasm_host {
   use crate::melt;
+  use serde_bytes::ByteBuf;
   use serde_cbor::{from_reader, to_vec};
 
   pub use wasm_minimal_protocol::*;
@@ -9,9 +10,13 @@ mod wasm_host {
   initiate_protocol!();
 
   #[wasm_func]
-  pub fn fonts_collection_info(data: &[u8]) -> Vec<u8> {
-    to_vec(&melt::FontInfo::from_collections(data).collect::<Vec<_>>())
-      .unwrap()
+  pub fn fonts_collection_info(data: &[u8], options: &[u8]) -> Vec<u8> {
+    if let Ok(options) = from_reader::<melt::FontInfoOptions, _>(options) {
+      to_vec(&melt::fonts_collection_info(data, &options)).unwrap()
+    } else {
+      let result: Vec<Option<()>> = Vec::new();
+      to_vec(&result).unwrap()
+    }
   }
 
   #[wasm_func]
@@ -41,11 +46,57 @@ mod wasm_host {
     }
   }
 
+  #[wasm_func]
+  pub fn glyphs_glyf(
+    data: &[u8],
+    index: &[u8],
+    codepoints: &[u8],
+  ) -> Vec<u8> {
+    if let Ok(index) = from_reader::<u32, _>(index)
+      && let Ok(codepoints) =
+        from_reader::<Vec<u32>, _>(codepoints).map(|codes| {
+          codes
+            .into_iter()
+            .map(std::char::from_u32)
+            .collect::<Vec<Option<char>>>()
+        })
+    {
+      to_vec(&melt::glyphs_glyf(data, index, codepoints.into_iter())).unwrap()
+    } else {
+      let result: Vec<Option<char>> = Vec::new();
+      to_vec(&result).unwrap()
+    }
+  }
+
+  #[wasm_func]
+  pub fn glyphs_anchors(
+    data: &[u8],
+    index: &[u8],
+    codepoints: &[u8],
+  ) -> Vec<u8> {
+    if let Ok(index) = from_reader::<u32, _>(index)
+      && let Ok(codepoints) =
+        from_reader::<Vec<u32>, _>(codepoints).map(|codes| {
+          codes
+            .into_iter()
+            .map(std::char::from_u32)
+            .collect::<Vec<Option<char>>>()
+        })
+    {
+      to_vec(&melt::glyphs_anchors(data, index, codepoints.into_iter()))
+        .unwrap()
+    } else {
+      let result: Vec<Option<char>> = Vec::new();
+      to_vec(&result).unwrap()
+    }
+  }
+
   #[wasm_func]
   pub fn glyphs_shapes(
     data: &[u8],
     index: &[u8],
     codepoints: &[u8],
+    options: &[u8],
   ) -> Vec<u8> {
     if let Ok(index) = from_reader::<u32, _>(index)
       && let Ok(codepoints) =
@@ -55,11 +106,13 @@ mod wasm_host {
             .map(std::char::from_u32)
             .collect::<Vec<Option<char>>>()
         })
+      && let Ok(options) = from_reader::<melt::ShapeOptions, _>(options)
     {
       to_vec(&melt::glyphs_shapes(
         data,
         index,
         codepoints.clone().into_iter(),
+        &options,
       ))
       .unwrap()
     } else {
@@ -67,4 +120,287 @@ mod wasm_host {
       to_vec(&result).unwrap()
     }
   }
+
+  #[wasm_func]
+  pub fn glyphs_outlines(
+    data: &[u8],
+    index: &[u8],
+    codepoints: &[u8],
+    options: &[u8],
+  ) -> Vec<u8> {
+    if let Ok(index) = from_reader::<u32, _>(index)
+      && let Ok(codepoints) =
+        from_reader::<Vec<u32>, _>(codepoints).map(|codes| {
+          codes
+            .into_iter()
+            .map(std::char::from_u32)
+            .collect::<Vec<Option<char>>>()
+        })
+      && let Ok(options) = from_reader::<melt::OutlineOptions, _>(options)
+    {
+      to_vec(&melt::glyphs_outlines(
+        data,
+        index,
+        codepoints.into_iter(),
+        &options,
+      ))
+      .unwrap()
+    } else {
+      let result: Vec<Option<char>> = Vec::new();
+      to_vec(&result).unwrap()
+    }
+  }
+
+  #[wasm_func]
+  pub fn glyphs_hinted_outlines(
+    data: &[u8],
+    index: &[u8],
+    codepoints: &[u8],
+    options: &[u8],
+  ) -> Vec<u8> {
+    if let Ok(index) = from_reader::<u32, _>(index)
+      && let Ok(codepoints) =
+        from_reader::<Vec<u32>, _>(codepoints).map(|codes| {
+          codes
+            .into_iter()
+            .map(std::char::from_u32)
+            .collect::<Vec<Option<char>>>()
+        })
+      && let Ok(options) =
+        from_reader::<melt::HintedOutlineOptions, _>(options)
+    {
+      to_vec(&melt::glyphs_hinted_outlines(
+        data,
+        index,
+        codepoints.into_iter(),
+        &options,
+      ))
+      .unwrap()
+    } else {
+      let result: Vec<Option<char>> = Vec::new();
+      to_vec(&result).unwrap()
+    }
+  }
+
+  #[wasm_func]
+  pub fn load_font(data: &[u8], index: &[u8]) -> Vec<u8> {
+    let handle = from_reader::<u32, _>(index)
+      .ok()
+      .and_then(|index| melt::load_font(data, index));
+    to_vec(&handle).unwrap()
+  }
+
+  #[wasm_func]
+  pub fn last_loaded_font() -> Vec<u8> {
+    to_vec(&melt::last_loaded_font()).unwrap()
+  }
+
+  #[wasm_func]
+  pub fn loaded_fonts() -> Vec<u8> {
+    to_vec(&melt::loaded_fonts()).unwrap()
+  }
+
+  #[wasm_func]
+  pub fn glyphs_infos_by_handle(handle: &[u8], codepoints: &[u8]) -> Vec<u8> {
+    if let Ok(handle) = from_reader::<u64, _>(handle)
+      && let Ok(codepoints) =
+        from_reader::<Vec<u32>, _>(codepoints).map(|codes| {
+          codes
+            .into_iter()
+            .map(std::char::from_u32)
+            .collect::<Vec<Option<char>>>()
+        })
+    {
+      to_vec(&melt::glyphs_infos_by_handle(
+        handle,
+        codepoints.into_iter(),
+      ))
+      .unwrap()
+    } else {
+      let result: Vec<Option<char>> = Vec::new();
+      to_vec(&result).unwrap()
+    }
+  }
+
+  #[wasm_func]
+  pub fn glyphs_shapes_by_handle(
+    handle: &[u8],
+    codepoints: &[u8],
+    options: &[u8],
+  ) -> Vec<u8> {
+    if let Ok(handle) = from_reader::<u64, _>(handle)
+      && let Ok(codepoints) =
+        from_reader::<Vec<u32>, _>(codepoints).map(|codes| {
+          codes
+            .into_iter()
+            .map(std::char::from_u32)
+            .collect::<Vec<Option<char>>>()
+        })
+      && let Ok(options) = from_reader::<melt::ShapeOptions, _>(options)
+    {
+      to_vec(&melt::glyphs_shapes_by_handle(
+        handle,
+        codepoints.into_iter(),
+        &options,
+      ))
+      .unwrap()
+    } else {
+      let result: Vec<Option<char>> = Vec::new();
+      to_vec(&result).unwrap()
+    }
+  }
+
+  #[wasm_func]
+  pub fn fonts_fallback(text: &[u8], fonts: &[u8]) -> Vec<u8> {
+    if let Ok(text) = from_reader::<String, _>(text)
+      && let Ok(fonts) = from_reader::<Vec<(ByteBuf, u32)>, _>(fonts)
+    {
+      to_vec(&melt::fonts_fallback(
+        &text,
+        fonts.iter().map(|(data, index)| (data.as_slice(), *index)),
+      ))
+      .unwrap()
+    } else {
+      let result: Option<()> = None;
+      to_vec(&result).unwrap()
+    }
+  }
+
+  #[wasm_func]
+  pub fn parse_glyph_names(names: &[u8]) -> Vec<u8> {
+    if let Ok(names) = from_reader::<Vec<String>, _>(names) {
+      to_vec(&melt::parse_glyph_names(&names)).unwrap()
+    } else {
+      let result: Vec<()> = Vec::new();
+      to_vec(&result).unwrap()
+    }
+  }
+
+  #[wasm_func]
+  pub fn resolve_names(data: &[u8], index: &[u8], options: &[u8]) -> Vec<u8> {
+    if let Ok(index) = from_reader::<u32, _>(index)
+      && let Ok(options) = from_reader::<melt::NameResolveOptions, _>(options)
+    {
+      to_vec(&melt::resolve_names(data, index, &options)).unwrap()
+    } else {
+      let result: Option<()> = None;
+      to_vec(&result).unwrap()
+    }
+  }
+
+  #[wasm_func]
+  pub fn opentype_tags(languages: &[u8]) -> Vec<u8> {
+    if let Ok(languages) = from_reader::<Vec<String>, _>(languages) {
+      to_vec(&melt::opentype_tags(&languages)).unwrap()
+    } else {
+      let result: Vec<()> = Vec::new();
+      to_vec(&result).unwrap()
+    }
+  }
+
+  #[wasm_func]
+  pub fn char_properties(codepoints: &[u8]) -> Vec<u8> {
+    if let Ok(codepoints) = from_reader::<Vec<u32>, _>(codepoints) {
+      to_vec(&melt::char_properties(&codepoints)).unwrap()
+    } else {
+      let result: Vec<()> = Vec::new();
+      to_vec(&result).unwrap()
+    }
+  }
+
+  #[wasm_func]
+  pub fn measure_text(
+    data: &[u8],
+    index: &[u8],
+    text: &[u8],
+    options: &[u8],
+  ) -> Vec<u8> {
+    if let Ok(index) = from_reader::<u32, _>(index)
+      && let Ok(text) = from_reader::<String, _>(text)
+      && let Ok(options) = from_reader::<melt::ShapingOptions, _>(options)
+    {
+      to_vec(&melt::measure_text(data, index, &text, &options)).unwrap()
+    } else {
+      let result: Option<()> = None;
+      to_vec(&result).unwrap()
+    }
+  }
+
+  #[wasm_func]
+  pub fn glyphs_rasters(
+    data: &[u8],
+    index: &[u8],
+    codepoints: &[u8],
+    options: &[u8],
+  ) -> Vec<u8> {
+    if let Ok(index) = from_reader::<u32, _>(index)
+      && let Ok(codepoints) =
+        from_reader::<Vec<u32>, _>(codepoints).map(|codes| {
+          codes
+            .into_iter()
+            .map(std::char::from_u32)
+            .collect::<Vec<Option<char>>>()
+        })
+      && let Ok(options) = from_reader::<melt::RasterOptions, _>(options)
+    {
+      to_vec(&melt::glyphs_rasters(
+        data,
+        index,
+        codepoints.into_iter(),
+        &options,
+      ))
+      .unwrap()
+    } else {
+      let result: Vec<Option<char>> = Vec::new();
+      to_vec(&result).unwrap()
+    }
+  }
+
+  #[wasm_func]
+  pub fn text_raster(
+    data: &[u8],
+    index: &[u8],
+    text: &[u8],
+    options: &[u8],
+  ) -> Vec<u8> {
+    if let Ok(index) = from_reader::<u32, _>(index)
+      && let Ok(text) = from_reader::<String, _>(text)
+      && let Ok(options) = from_reader::<melt::TextRasterOptions, _>(options)
+    {
+      to_vec(&melt::text_raster(data, index, &text, &options)).unwrap()
+    } else {
+      let result: Option<()> = None;
+      to_vec(&result).unwrap()
+    }
+  }
+
+  #[wasm_func]
+  pub fn glyph_grid(data: &[u8], index: &[u8], options: &[u8]) -> Vec<u8> {
+    if let Ok(index) = from_reader::<u32, _>(index)
+      && let Ok(options) = from_reader::<melt::GlyphGridOptions, _>(options)
+    {
+      to_vec(&melt::glyph_grid(data, index, &options)).unwrap()
+    } else {
+      let result: Option<()> = None;
+      to_vec(&result).unwrap()
+    }
+  }
+
+  #[wasm_func]
+  pub fn text_to_svg(
+    data: &[u8],
+    index: &[u8],
+    text: &[u8],
+    options: &[u8],
+  ) -> Vec<u8> {
+    if let Ok(index) = from_reader::<u32, _>(index)
+      && let Ok(text) = from_reader::<String, _>(text)
+      && let Ok(options) = from_reader::<melt::TextSvgOptions, _>(options)
+    {
+      to_vec(&melt::text_to_svg(data, index, &text, &options)).unwrap()
+    } else {
+      let result: Option<()> = None;
+      to_vec(&result).unwrap()
+    }
+  }
 }
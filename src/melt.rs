@@ -1,54 +1,230 @@
+mod bcp47;
+mod cache;
+mod container;
+mod fallback;
+mod raster;
 mod repr;
+mod select;
+mod shaping;
+mod specimen;
+mod text_svg;
 pub mod ttf;
 mod typst;
+mod ucd;
 
+use container::Container;
+use fallback::FontsFallback;
+use raster::{Bitmap, GlyphsBitmaps};
+pub(crate) use raster::{RasterOptions, TextRasterOptions};
 use repr::FontRepr;
+use select::FieldSelector;
 use serde::{Deserialize, Serialize};
+use serde_cbor::Value;
+pub(crate) use shaping::ShapingOptions;
+use shaping::TextMeasure;
+use specimen::GlyphGrid;
+pub(crate) use specimen::GlyphGridOptions;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use text_svg::TextSvg;
+pub(crate) use text_svg::TextSvgOptions;
 
-use ttf::features::FontFeatures;
+use ttf::agl::ParsedGlyphName;
+use ttf::anchors::GlyphsAnchors;
+use ttf::baselines::FontBaselines;
+use ttf::cff::FontCff;
+use ttf::features::{FeatureTagInfo, FontFeatures};
+use ttf::gdef::FontGdef;
+use ttf::glyf::GlyphsGlyf;
+pub(crate) use ttf::glyphs::ShapeOptions;
 use ttf::glyphs::{GlyphsInfo, GlyphsShapes};
+pub(crate) use ttf::hinting::HintedOutlineOptions;
+use ttf::hinting::HintedOutlines;
+use ttf::instances::FontInstance;
+use ttf::meta::FontMeta;
 use ttf::metrics::FontMetrics;
 use ttf::names::FontNames;
-use ttf::scripts::FontScripts;
+pub(crate) use ttf::names::NameResolveOptions;
+use ttf::names::ResolvedName;
+use ttf::outline::GlyphsOutlines;
+pub(crate) use ttf::outline::OutlineOptions;
+use ttf::scripts::{FontScripts, OpenTypeTags};
 use typst::TypstFontIntrospection;
+use ucd::CharProperties;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct FontProperties {
-  names: FontNames,
-  scripts: FontScripts,
-  features: FontFeatures,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  names: Option<FontNames>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  scripts: Option<FontScripts>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  features: Option<FontFeatures>,
+  // registered names and categories of `features`, in order
+  #[serde(skip_serializing_if = "Option::is_none")]
+  feature_tags: Option<Vec<FeatureTagInfo>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  meta: Option<FontMeta>,
 }
 
 impl FontProperties {
-  fn from_repr(repr: &FontRepr) -> Self {
-    FontProperties {
-      names: FontNames::from_repr(repr),
-      scripts: FontScripts::from_repr(repr),
-      features: FontFeatures::from_repr(repr),
-    }
+  fn from_repr(repr: &FontRepr, selector: &FieldSelector) -> Option<Self> {
+    selector.wants("properties").then(|| {
+      let wants_features = selector.wants("properties.features");
+      let wants_tags = selector.wants("properties.feature_tags");
+      // read once for both fields
+      let features =
+        (wants_features || wants_tags).then(|| FontFeatures::from_repr(repr));
+      let feature_tags = features
+        .as_ref()
+        .filter(|_| wants_tags)
+        .map(FontFeatures::tags);
+      FontProperties {
+        names: selector
+          .wants("properties.names")
+          .then(|| FontNames::from_repr(repr)),
+        scripts: selector
+          .wants("properties.scripts")
+          .then(|| FontScripts::from_repr(repr)),
+        features: features.filter(|_| wants_features),
+        feature_tags,
+        meta: selector
+          .wants("properties.meta")
+          .then(|| FontMeta::from_repr(repr)),
+      }
+    })
   }
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct FontInfoOptions {
+  // dot separated paths of fields to be computed, all fields if `None`
+  fields: Option<Vec<String>>,
+  // one entry per named instance of variable fonts
+  instances: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FontInfo {
-  properties: FontProperties,
-  metrics: FontMetrics,
-  typst: TypstFontIntrospection,
+  // the named instance described, see `FontInfo::from_instances`
+  #[serde(skip_serializing_if = "Option::is_none")]
+  instance: Option<FontInstance>,
+  // the format of the file the font is read from, see `Container`
+  #[serde(skip_serializing_if = "Option::is_none")]
+  container: Option<Container>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  properties: Option<FontProperties>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  metrics: Option<FontMetrics>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  gdef: Option<FontGdef>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  baselines: Option<FontBaselines>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  cff: Option<FontCff>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  typst: Option<TypstFontIntrospection>,
 }
 
 impl FontInfo {
-  pub fn new(data: &[u8], index: u32) -> Option<Self> {
-    let repr = FontRepr::new(data, index)?;
-    Some(FontInfo {
-      properties: FontProperties::from_repr(&repr),
-      metrics: FontMetrics::from_repr(&repr),
-      typst: TypstFontIntrospection::from_repr(&repr),
-    })
+  fn from_repr(repr: &FontRepr, selector: &FieldSelector) -> Self {
+    FontInfo {
+      instance: None,
+      container: None,
+      properties: FontProperties::from_repr(repr, selector),
+      metrics: selector
+        .wants("metrics")
+        .then(|| FontMetrics::from_repr(repr)),
+      gdef: selector.wants("gdef").then(|| FontGdef::from_repr(repr)),
+      baselines: selector
+        .wants("baselines")
+        .then(|| FontBaselines::from_repr(repr)),
+      cff: selector.wants("cff").then(|| FontCff::from_repr(repr)),
+      typst: TypstFontIntrospection::from_repr(repr, selector),
+    }
   }
 
-  pub fn from_collections(data: &[u8]) -> impl Iterator<Item = Option<Self>> {
+  /// Only fields selected by `selector` are computed.
+  pub fn new(
+    data: &[u8],
+    index: u32,
+    selector: &FieldSelector,
+  ) -> Option<Self> {
+    let data = container::unpack(data);
+    let repr = FontRepr::new(&data, index)?;
+    Some(FontInfo::from_repr(&repr, selector))
+  }
+
+  pub fn from_collections<'a>(
+    data: &'a [u8],
+    selector: &'a FieldSelector,
+  ) -> impl Iterator<Item = Option<Self>> + 'a {
     let counts = ttf_parser::fonts_in_collection(data).unwrap_or(1);
-    (0..counts).map(move |id| FontInfo::new(data, id))
+    (0..counts).map(move |id| FontInfo::new(data, id, selector))
+  }
+
+  /// Like `FontInfo::from_collections`, but yields one entry per named
+  /// instance of variable fonts. Fonts without named instances yield a
+  /// single entry. Fields other than `instance` describe the default
+  /// instance.
+  pub fn from_instances<'a>(
+    data: &'a [u8],
+    selector: &'a FieldSelector,
+  ) -> impl Iterator<Item = Option<Self>> + 'a {
+    let counts = ttf_parser::fonts_in_collection(data).unwrap_or(1);
+    (0..counts).flat_map(move |id| {
+      let Some(repr) = FontRepr::new(data, id) else {
+        return vec![None];
+      };
+      let info = FontInfo::from_repr(&repr, selector);
+      let instances = FontInstance::from_repr(&repr, id);
+      if instances.is_empty() {
+        return vec![Some(info)];
+      }
+      instances
+        .into_iter()
+        .map(|instance| {
+          Some(FontInfo {
+            instance: Some(instance),
+            ..info.clone()
+          })
+        })
+        .collect()
+    })
+  }
+}
+
+/// Like `FontInfo::from_collections`, but unselected fields are dropped
+/// from the serialized value as well.
+pub fn fonts_collection_info(
+  data: &[u8],
+  options: &FontInfoOptions,
+) -> Vec<Option<Value>> {
+  let mut fields = options.fields.clone();
+  // entries of instances could not be told apart otherwise
+  if options.instances
+    && let Some(fields) = &mut fields
+  {
+    fields.push("instance".to_string());
+  }
+  let selector = FieldSelector::new(fields);
+  let container = selector.wants("container").then(|| Container::of(data));
+  let data = container::unpack(data);
+  let to_value = |info: Option<FontInfo>| {
+    let info = FontInfo { container, ..info? };
+    serde_cbor::value::to_value(info)
+      .ok()
+      .map(|value| selector.prune(value))
+  };
+  if options.instances {
+    FontInfo::from_instances(&data, &selector)
+      .map(to_value)
+      .collect()
+  } else {
+    FontInfo::from_collections(&data, &selector)
+      .map(to_value)
+      .collect()
   }
 }
 
@@ -57,15 +233,190 @@ pub fn glyphs_infos(
   index: u32,
   codes: impl Iterator<Item = Option<char>>,
 ) -> Option<GlyphsInfo> {
-  let repr = FontRepr::new(data, index)?;
+  let data = container::unpack(data);
+  let repr = FontRepr::new(&data, index)?;
   Some(GlyphsInfo::from_option_iter(&repr, codes))
 }
 
+pub fn glyphs_infos_by_handle(
+  handle: u64,
+  codes: impl Iterator<Item = Option<char>>,
+) -> Option<GlyphsInfo> {
+  cache::with_font(handle, |repr| GlyphsInfo::from_option_iter(repr, codes))
+}
+
+pub fn glyphs_glyf(
+  data: &[u8],
+  index: u32,
+  codes: impl Iterator<Item = Option<char>>,
+) -> Option<GlyphsGlyf> {
+  let data = container::unpack(data);
+  let repr = FontRepr::new(&data, index)?;
+  GlyphsGlyf::from_option_iter(&repr, codes)
+}
+
+pub fn glyphs_anchors(
+  data: &[u8],
+  index: u32,
+  codes: impl Iterator<Item = Option<char>>,
+) -> Option<GlyphsAnchors> {
+  let data = container::unpack(data);
+  let repr = FontRepr::new(&data, index)?;
+  Some(GlyphsAnchors::from_option_iter(&repr, codes))
+}
+
 pub fn glyphs_shapes(
   data: &[u8],
   index: u32,
   codes: impl Iterator<Item = Option<char>>,
+  options: &ShapeOptions,
+) -> Option<GlyphsShapes> {
+  let data = container::unpack(data);
+  let repr = FontRepr::new(&data, index)?;
+  GlyphsShapes::from_option_iter(&repr, codes, options)
+}
+
+pub fn glyphs_outlines(
+  data: &[u8],
+  index: u32,
+  codes: impl Iterator<Item = Option<char>>,
+  options: &OutlineOptions,
+) -> Option<GlyphsOutlines> {
+  let data = container::unpack(data);
+  let repr = FontRepr::new(&data, index)?;
+  Some(GlyphsOutlines::from_option_iter(&repr, codes, options))
+}
+
+pub fn glyphs_hinted_outlines(
+  data: &[u8],
+  index: u32,
+  codes: impl Iterator<Item = Option<char>>,
+  options: &HintedOutlineOptions,
+) -> Option<HintedOutlines> {
+  let data = container::unpack(data);
+  let repr = FontRepr::new(&data, index)?;
+  HintedOutlines::from_option_iter(&repr, codes, options)
+}
+
+pub fn glyphs_shapes_by_handle(
+  handle: u64,
+  codes: impl Iterator<Item = Option<char>>,
+  options: &ShapeOptions,
 ) -> Option<GlyphsShapes> {
-  let repr = FontRepr::new(data, index)?;
-  Some(GlyphsShapes::from_option_iter(&repr, codes))
+  cache::with_font(handle, |repr| {
+    GlyphsShapes::from_option_iter(repr, codes, options)
+  })
+  .flatten()
+}
+
+pub fn load_font(data: &[u8], index: u32) -> Option<u64> {
+  cache::load(data, index)
+}
+
+pub fn last_loaded_font() -> Option<u64> {
+  cache::last_loaded()
+}
+
+pub fn loaded_fonts() -> Vec<u64> {
+  cache::handles()
+}
+
+pub fn fonts_fallback<'a>(
+  text: &str,
+  fonts: impl Iterator<Item = (&'a [u8], u32)>,
+) -> FontsFallback {
+  let fonts: Vec<(Cow<[u8]>, u32)> = fonts
+    .map(|(data, index)| (container::unpack(data), index))
+    .collect();
+  let reprs: Vec<Option<FontRepr>> = fonts
+    .iter()
+    .map(|(data, index)| FontRepr::new(data, *index))
+    .collect();
+  FontsFallback::from_reprs(text, &reprs)
+}
+
+pub fn parse_glyph_names(names: &[String]) -> Vec<ParsedGlyphName> {
+  names
+    .iter()
+    .map(|name| ParsedGlyphName::parse(name))
+    .collect()
+}
+
+/// The best name for each name id, see `NameResolveOptions`.
+pub fn resolve_names(
+  data: &[u8],
+  index: u32,
+  options: &NameResolveOptions,
+) -> Option<BTreeMap<&'static str, ResolvedName>> {
+  let data = container::unpack(data);
+  let repr = FontRepr::new(&data, index)?;
+  Some(ttf::names::resolve_names(&repr, options))
+}
+
+pub fn opentype_tags(languages: &[String]) -> Vec<OpenTypeTags> {
+  languages
+    .iter()
+    .map(|language| OpenTypeTags::from_language(language))
+    .collect()
+}
+
+pub fn char_properties(codes: &[u32]) -> Vec<Option<CharProperties>> {
+  codes
+    .iter()
+    .map(|&code| CharProperties::from_codepoint(code))
+    .collect()
+}
+
+pub fn measure_text(
+  data: &[u8],
+  index: u32,
+  text: &str,
+  options: &ShapingOptions,
+) -> Option<TextMeasure> {
+  let data = container::unpack(data);
+  let repr = FontRepr::new(&data, index)?;
+  Some(TextMeasure::from_repr(&repr, text, options))
+}
+
+pub fn glyphs_rasters(
+  data: &[u8],
+  index: u32,
+  codes: impl Iterator<Item = Option<char>>,
+  options: &RasterOptions,
+) -> Option<GlyphsBitmaps> {
+  let data = container::unpack(data);
+  let repr = FontRepr::new(&data, index)?;
+  GlyphsBitmaps::from_option_iter(&repr, codes, options)
+}
+
+pub fn text_raster(
+  data: &[u8],
+  index: u32,
+  text: &str,
+  options: &TextRasterOptions,
+) -> Option<Bitmap> {
+  let data = container::unpack(data);
+  let repr = FontRepr::new(&data, index)?;
+  Bitmap::from_text(&repr, text, options)
+}
+
+pub fn glyph_grid(
+  data: &[u8],
+  index: u32,
+  options: &GlyphGridOptions,
+) -> Option<GlyphGrid> {
+  let data = container::unpack(data);
+  let repr = FontRepr::new(&data, index)?;
+  GlyphGrid::from_repr(&repr, options)
+}
+
+pub fn text_to_svg(
+  data: &[u8],
+  index: u32,
+  text: &str,
+  options: &TextSvgOptions,
+) -> Option<TextSvg> {
+  let data = container::unpack(data);
+  let repr = FontRepr::new(&data, index)?;
+  TextSvg::from_repr(&repr, text, options)
 }
@@ -0,0 +1,225 @@
+/// This mod parses BCP 47 language tags, e.g. `zh-Hant-TW`, into their
+/// subtags and checks them against the validity data of Unicode CLDR. The
+/// tables in `bcp47/` are generated by `scripts/subtags.py`.
+/// - see: <https://www.rfc-editor.org/rfc/rfc5646>
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+/// Valid subtags in lowercase, ranges like `qaa..qaz` as `(start, end)`.
+struct Subtags {
+  subtags: Vec<String>,
+  ranges: Vec<(String, String)>,
+}
+
+impl Subtags {
+  fn new(data: &'static str) -> Self {
+    let mut subtags = Vec::new();
+    let mut ranges = Vec::new();
+    for line in data.lines().filter(|line| !line.starts_with('#')) {
+      let subtag = line.split(';').next().unwrap_or_default();
+      let subtag = subtag.to_ascii_lowercase();
+      if let Some((start, end)) = subtag.split_once("..") {
+        ranges.push((start.to_string(), end.to_string()));
+      } else {
+        subtags.push(subtag);
+      }
+    }
+    subtags.sort_unstable();
+    Subtags { subtags, ranges }
+  }
+
+  /// `subtag` must be in lowercase.
+  fn contains(&self, subtag: &str) -> bool {
+    self
+      .subtags
+      .binary_search_by(|s| s.as_str().cmp(subtag))
+      .is_ok()
+      || self.ranges.iter().any(|(start, end)| {
+        subtag.len() == start.len()
+          && start.as_str() <= subtag
+          && subtag <= end.as_str()
+      })
+  }
+}
+
+static LANGUAGES: LazyLock<Subtags> =
+  LazyLock::new(|| Subtags::new(include_str!("bcp47/language.txt")));
+static SCRIPTS: LazyLock<Subtags> =
+  LazyLock::new(|| Subtags::new(include_str!("bcp47/script.txt")));
+static REGIONS: LazyLock<Subtags> =
+  LazyLock::new(|| Subtags::new(include_str!("bcp47/region.txt")));
+static VARIANTS: LazyLock<Subtags> =
+  LazyLock::new(|| Subtags::new(include_str!("bcp47/variant.txt")));
+static GRANDFATHERED: LazyLock<Subtags> =
+  LazyLock::new(|| Subtags::new(include_str!("bcp47/grandfathered.txt")));
+
+fn is_alpha(subtag: &str, lengths: std::ops::RangeInclusive<usize>) -> bool {
+  lengths.contains(&subtag.len())
+    && subtag.bytes().all(|byte| byte.is_ascii_alphabetic())
+}
+
+fn is_alphanumeric(
+  subtag: &str,
+  lengths: std::ops::RangeInclusive<usize>,
+) -> bool {
+  lengths.contains(&subtag.len())
+    && subtag.bytes().all(|byte| byte.is_ascii_alphanumeric())
+}
+
+fn title_case(subtag: &str) -> String {
+  subtag
+    .char_indices()
+    .map(|(index, c)| {
+      if index == 0 {
+        c.to_ascii_uppercase()
+      } else {
+        c
+      }
+    })
+    .collect()
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct LanguageTag {
+  // as given, e.g. `zh-hant-tw`
+  tag: String,
+  // subtags in their canonical case, e.g. `zh`, `Hant` and `TW`
+  pub(crate) language: Option<String>,
+  // e.g. `yue` of `zh-yue`
+  pub(crate) extended_languages: Vec<String>,
+  pub(crate) script: Option<String>,
+  // two letters or three digits, e.g. `419` for Latin America
+  pub(crate) region: Option<String>,
+  pub(crate) variants: Vec<String>,
+  // singleton and its subtags, e.g. `u-nu-arab`
+  extensions: Vec<String>,
+  // e.g. `x-twain`
+  pub(crate) private_use: Option<String>,
+  // whether the tag follows the syntax of BCP 47
+  well_formed: bool,
+  // whether the tag is well-formed and all of its subtags are valid,
+  // extensions are not checked
+  valid: bool,
+}
+
+impl LanguageTag {
+  /// Parses a tag as BCP 47 does, save that it may start with a script, as
+  /// `ScriptLangTag`s of the `meta` table may, e.g. `Cyrl` or `Hant-HK`.
+  pub(crate) fn parse(tag: &str) -> Self {
+    let mut parsed = LanguageTag {
+      tag: tag.to_string(),
+      ..LanguageTag::default()
+    };
+    let lowercase = tag.to_ascii_lowercase();
+    if GRANDFATHERED.contains(&lowercase) {
+      parsed.well_formed = true;
+      parsed.valid = true;
+      return parsed;
+    }
+    let subtags: Vec<&str> = lowercase.split('-').collect();
+    if parsed.parse_subtags(&subtags).is_none() {
+      return LanguageTag {
+        tag: tag.to_string(),
+        ..LanguageTag::default()
+      };
+    }
+    parsed.well_formed = true;
+    parsed.valid = parsed.has_valid_subtags();
+    parsed
+  }
+
+  /// `None` if the lowercase subtags are not well-formed.
+  fn parse_subtags(&mut self, subtags: &[&str]) -> Option<()> {
+    let mut rest = subtags.iter().copied().peekable();
+    let first = *rest.peek()?;
+    if is_alpha(first, 2..=3) {
+      self.language = Some(first.to_string());
+      rest.next();
+      while self.extended_languages.len() < 3
+        && let Some(extended) = rest.next_if(|subtag| is_alpha(subtag, 3..=3))
+      {
+        self.extended_languages.push(extended.to_string());
+      }
+    } else if is_alpha(first, 5..=8) {
+      self.language = Some(first.to_string());
+      rest.next();
+    } else if first != "x" && !is_alpha(first, 4..=4) {
+      return None;
+    }
+    if let Some(script) = rest.next_if(|subtag| is_alpha(subtag, 4..=4)) {
+      self.script = Some(title_case(script));
+    }
+    if let Some(region) = rest.next_if(|subtag| {
+      is_alpha(subtag, 2..=2)
+        || (subtag.len() == 3 && subtag.bytes().all(|b| b.is_ascii_digit()))
+    }) {
+      self.region = Some(region.to_ascii_uppercase());
+    }
+    while let Some(variant) = rest.next_if(|subtag| {
+      is_alphanumeric(subtag, 5..=8)
+        || (is_alphanumeric(subtag, 4..=4)
+          && subtag.starts_with(|c: char| c.is_ascii_digit()))
+    }) {
+      self.variants.push(variant.to_string());
+    }
+    while let Some(singleton) =
+      rest.next_if(|subtag| is_alphanumeric(subtag, 1..=1) && *subtag != "x")
+    {
+      let mut extension = singleton.to_string();
+      while let Some(subtag) =
+        rest.next_if(|subtag| is_alphanumeric(subtag, 2..=8))
+      {
+        extension.push('-');
+        extension.push_str(subtag);
+      }
+      if extension.len() == 1 {
+        return None;
+      }
+      self.extensions.push(extension);
+    }
+    if rest.next_if_eq(&"x").is_some() {
+      let private: Vec<&str> = rest.by_ref().collect();
+      if private.is_empty()
+        || !private.iter().all(|subtag| is_alphanumeric(subtag, 1..=8))
+      {
+        return None;
+      }
+      self.private_use = Some(format!("x-{}", private.join("-")));
+    }
+    if rest.next().is_some()
+      || (self.language.is_none()
+        && self.script.is_none()
+        && self.private_use.is_none())
+    {
+      return None;
+    }
+    Some(())
+  }
+
+  fn has_valid_subtags(&self) -> bool {
+    let mut singletons: Vec<&str> =
+      self.extensions.iter().map(|e| &e[..1]).collect();
+    singletons.sort_unstable();
+    let mut variants: Vec<&String> = self.variants.iter().collect();
+    variants.sort_unstable();
+    self
+      .language
+      .iter()
+      .chain(&self.extended_languages)
+      .all(|language| LANGUAGES.contains(language))
+      && self
+        .script
+        .as_ref()
+        .is_none_or(|script| SCRIPTS.contains(&script.to_ascii_lowercase()))
+      && self
+        .region
+        .as_ref()
+        .is_none_or(|region| REGIONS.contains(&region.to_ascii_lowercase()))
+      && self
+        .variants
+        .iter()
+        .all(|variant| VARIANTS.contains(variant))
+      && variants.windows(2).all(|pair| pair[0] != pair[1])
+      && singletons.windows(2).all(|pair| pair[0] != pair[1])
+  }
+}
@@ -0,0 +1,101 @@
+/// This module keeps parsed fonts alive between plugin calls, so that
+/// repeated calls on the same font do not parse it again.
+///
+/// Typst discards the state mutated by a plugin call unless the call is
+/// made through `plugin.transition`, see `load-font` in `lib.typ`.
+use super::container;
+use super::repr::FontRepr;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// Maximum number of fonts kept, the least recently used one is evicted
+/// first.
+const CAPACITY: usize = 8;
+
+struct CachedFont {
+  handle: u64,
+  data: Vec<u8>,
+  index: u32,
+}
+
+#[derive(Default)]
+struct FontCache {
+  // ordered from the least to the most recently used
+  fonts: VecDeque<CachedFont>,
+  last_loaded: Option<u64>,
+}
+
+thread_local! {
+  static CACHE: RefCell<FontCache> = RefCell::default();
+}
+
+/// Handles are content hashes, truncated to fit in a Typst `int`.
+fn handle_of(data: &[u8], index: u32) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  data.hash(&mut hasher);
+  index.hash(&mut hasher);
+  hasher.finish() >> 1
+}
+
+impl FontCache {
+  fn touch(&mut self, handle: u64) -> Option<&CachedFont> {
+    let position =
+      self.fonts.iter().position(|font| font.handle == handle)?;
+    let font = self.fonts.remove(position)?;
+    self.fonts.push_back(font);
+    self.fonts.back()
+  }
+
+  fn load(&mut self, data: &[u8], index: u32) -> Option<u64> {
+    let handle = handle_of(data, index);
+    if self.touch(handle).is_none() {
+      let data = container::unpack(data);
+      // the font is only checked here, parsed again on each use
+      FontRepr::new(&data, index)?;
+      if self.fonts.len() >= CAPACITY {
+        self.fonts.pop_front();
+      }
+      self.fonts.push_back(CachedFont {
+        handle,
+        data: data.into_owned(),
+        index,
+      });
+    }
+    Some(handle)
+  }
+}
+
+/// Parses the font and keeps it, returns its handle.
+pub(crate) fn load(data: &[u8], index: u32) -> Option<u64> {
+  CACHE.with_borrow_mut(|cache| {
+    let handle = cache.load(data, index);
+    cache.last_loaded = handle;
+    handle
+  })
+}
+
+/// Handle of the font loaded by the latest `load` call, `None` if it
+/// failed.
+pub(crate) fn last_loaded() -> Option<u64> {
+  CACHE.with_borrow(|cache| cache.last_loaded)
+}
+
+/// Handles of all kept fonts, from the least to the most recently used.
+pub(crate) fn handles() -> Vec<u64> {
+  CACHE
+    .with_borrow(|cache| cache.fonts.iter().map(|font| font.handle).collect())
+}
+
+/// Runs `f` on the font of the handle, `None` if it is not (or no more)
+/// kept.
+pub(crate) fn with_font<R>(
+  handle: u64,
+  f: impl FnOnce(&FontRepr) -> R,
+) -> Option<R> {
+  CACHE.with_borrow_mut(|cache| {
+    let font = cache.touch(handle)?;
+    let repr = FontRepr::new(&font.data, font.index)?;
+    Some(f(&repr))
+  })
+}
@@ -0,0 +1,311 @@
+/// This mod tells the container format of font files and unpacks WOFF and
+/// WOFF2 files into the sfnt data the rest of the crate reads, so web fonts
+/// are accepted wherever font data is.
+/// - see: <https://www.w3.org/TR/WOFF/>
+/// - see: <https://www.w3.org/TR/WOFF2/>
+mod woff2;
+
+use flate2::read::ZlibDecoder;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::io::Read;
+use ttf_parser::RawFace;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Container {
+  // a single TrueType or OpenType font
+  Sfnt,
+  // a TrueType or OpenType collection
+  Ttc,
+  Woff,
+  // either a single font or a collection
+  Woff2,
+}
+
+impl Container {
+  /// Told by the signature, data of unknown formats is taken as `Sfnt`.
+  pub(crate) fn of(data: &[u8]) -> Self {
+    match data.get(..4) {
+      Some(b"wOFF") => Container::Woff,
+      Some(b"wOF2") => Container::Woff2,
+      Some(b"ttcf") => Container::Ttc,
+      _ => Container::Sfnt,
+    }
+  }
+}
+
+/// The sfnt data of a font file, WOFF and WOFF2 files unpacked. Other data,
+/// and files failing to unpack, are returned as given, so that they fail to
+/// parse as they did before.
+pub(crate) fn unpack(data: &[u8]) -> Cow<'_, [u8]> {
+  let unpacked = match Container::of(data) {
+    Container::Woff => unpack_woff(data),
+    Container::Woff2 => woff2::unpack(data),
+    Container::Sfnt | Container::Ttc => None,
+  };
+  unpacked.map_or(Cow::Borrowed(data), Cow::Owned)
+}
+
+// larger fonts are not unpacked, as table lengths of WOFF and WOFF2 files
+// would otherwise allocate as much as their headers claim
+const MAX_SFNT_SIZE: usize = 256 << 20;
+
+/// Reads big-endian fields, `None` past the end of data.
+#[derive(Clone)]
+struct Reader<'a> {
+  data: &'a [u8],
+  offset: usize,
+}
+
+impl<'a> Reader<'a> {
+  fn new(data: &'a [u8]) -> Self {
+    Reader { data, offset: 0 }
+  }
+
+  fn bytes(&mut self, length: usize) -> Option<&'a [u8]> {
+    let end = self.offset.checked_add(length)?;
+    let bytes = self.data.get(self.offset..end)?;
+    self.offset = end;
+    Some(bytes)
+  }
+
+  fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+    self.bytes(N)?.try_into().ok()
+  }
+
+  fn u8(&mut self) -> Option<u8> {
+    self.array().map(u8::from_be_bytes)
+  }
+
+  fn u16(&mut self) -> Option<u16> {
+    self.array().map(u16::from_be_bytes)
+  }
+
+  fn i16(&mut self) -> Option<i16> {
+    self.array().map(i16::from_be_bytes)
+  }
+
+  fn u32(&mut self) -> Option<u32> {
+    self.array().map(u32::from_be_bytes)
+  }
+
+  /// `totalSfntSize` of WOFF and WOFF2 headers, the size of the unpacked
+  /// font, `None` past `MAX_SFNT_SIZE`.
+  fn sfnt_size(&mut self) -> Option<usize> {
+    let size = self.u32()? as usize;
+    (size <= MAX_SFNT_SIZE).then_some(size)
+  }
+}
+
+struct Table {
+  tag: [u8; 4],
+  data: Vec<u8>,
+}
+
+impl Table {
+  fn checksum(&self) -> u32 {
+    self
+      .data
+      .chunks(4)
+      .enumerate()
+      // `checkSumAdjustment` of `head` is taken as zero
+      .filter(|(index, _)| &self.tag != b"head" || *index != 2)
+      .fold(0u32, |sum, (_, chunk)| {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+      })
+  }
+}
+
+/// A font of the unpacked file, with indices into its tables.
+struct Font {
+  flavor: u32,
+  tables: Vec<usize>,
+}
+
+/// Writes a single font, or a collection of fonts sharing `tables`, each
+/// table once and padded to four bytes.
+fn write_sfnt(
+  fonts: &[Font],
+  tables: &[Table],
+  collection: bool,
+) -> Option<Vec<u8>> {
+  let offset32 = |offset: usize| u32::try_from(offset).ok();
+  let mut offset = if collection { 12 + 4 * fonts.len() } else { 0 };
+  let mut directory_offsets = Vec::with_capacity(fonts.len());
+  for font in fonts {
+    directory_offsets.push(offset32(offset)?);
+    offset += 12 + 16 * font.tables.len();
+  }
+  let mut table_offsets = Vec::with_capacity(tables.len());
+  for table in tables {
+    table_offsets.push(offset32(offset)?);
+    offset += table.data.len().next_multiple_of(4);
+  }
+  offset32(offset)?;
+  let mut sfnt = Vec::with_capacity(offset);
+  if collection {
+    sfnt.extend(b"ttcf");
+    sfnt.extend(0x0001_0000u32.to_be_bytes());
+    sfnt.extend(offset32(fonts.len())?.to_be_bytes());
+    for directory_offset in directory_offsets {
+      sfnt.extend(directory_offset.to_be_bytes());
+    }
+  }
+  for font in fonts {
+    let num_tables = u16::try_from(font.tables.len()).ok()?;
+    let entry_selector = num_tables.max(1).ilog2();
+    let search_range = 16u16 << entry_selector;
+    let range_shift =
+      num_tables.saturating_mul(16).saturating_sub(search_range);
+    sfnt.extend(font.flavor.to_be_bytes());
+    sfnt.extend(num_tables.to_be_bytes());
+    sfnt.extend(search_range.to_be_bytes());
+    sfnt.extend(u16::try_from(entry_selector).ok()?.to_be_bytes());
+    sfnt.extend(range_shift.to_be_bytes());
+    let mut indices = font.tables.clone();
+    indices.sort_by_key(|&index| tables[index].tag);
+    for index in indices {
+      let table = &tables[index];
+      sfnt.extend(table.tag);
+      sfnt.extend(table.checksum().to_be_bytes());
+      sfnt.extend(table_offsets[index].to_be_bytes());
+      sfnt.extend(offset32(table.data.len())?.to_be_bytes());
+    }
+  }
+  for table in tables {
+    sfnt.extend(&table.data);
+    sfnt.resize(sfnt.len().next_multiple_of(4), 0);
+  }
+  Some(sfnt)
+}
+
+/// A font of only the tables of `face` with the given tags, copied as is,
+/// for reading a few tables without the cost of the others.
+pub(crate) fn with_tables(
+  face: &RawFace,
+  tags: &[&[u8; 4]],
+) -> Option<Vec<u8>> {
+  let tables = face
+    .table_records
+    .into_iter()
+    .filter(|record| tags.contains(&&record.tag.to_bytes()))
+    .map(|record| {
+      let offset = record.offset as usize;
+      let data = Reader {
+        data: face.data,
+        offset,
+      }
+      .bytes(record.length as usize)?
+      .to_vec();
+      Some(Table {
+        tag: record.tag.to_bytes(),
+        data,
+      })
+    })
+    .collect::<Option<Vec<Table>>>()?;
+  let font = Font {
+    flavor: 0x0001_0000,
+    tables: (0..tables.len()).collect(),
+  };
+  write_sfnt(&[font], &tables, false)
+}
+
+/// Tables of WOFF files are compressed with zlib one by one, unless that
+/// would not make them smaller.
+fn unpack_woff(data: &[u8]) -> Option<Vec<u8>> {
+  let mut header = Reader::new(data);
+  header.u32()?;
+  let flavor = header.u32()?;
+  header.u32()?;
+  let num_tables = header.u16()?;
+  header.u16()?;
+  // tables are rejected once their lengths add up past it
+  let mut remaining = header.sfnt_size()?;
+  // the rest of the 44 byte header, e.g. the extended metadata block
+  header.bytes(24)?;
+  let tables = (0..num_tables)
+    .map(|_| {
+      let tag = header.array()?;
+      let offset = header.u32()? as usize;
+      let compressed_length = header.u32()? as usize;
+      let length = header.u32()? as usize;
+      header.u32()?;
+      remaining = remaining.checked_sub(length)?;
+      let compressed = Reader { data, offset }.bytes(compressed_length)?;
+      let data = match compressed_length.cmp(&length) {
+        Ordering::Less => {
+          let mut table = Vec::new();
+          ZlibDecoder::new(compressed)
+            .take(length as u64)
+            .read_to_end(&mut table)
+            .ok()?;
+          (table.len() == length).then_some(table)?
+        }
+        Ordering::Equal => compressed.to_vec(),
+        Ordering::Greater => return None,
+      };
+      Some(Table { tag, data })
+    })
+    .collect::<Option<Vec<Table>>>()?;
+  let font = Font {
+    flavor,
+    tables: (0..tables.len()).collect(),
+  };
+  write_sfnt(&[font], &tables, false)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use flate2::Compression;
+  use flate2::write::ZlibEncoder;
+  use std::io::Write;
+
+  /// A WOFF file of a stored and a compressed table.
+  fn woff() -> Vec<u8> {
+    let stored = b"stored".to_vec();
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(&[7; 64]).unwrap();
+    let compressed = encoder.finish().unwrap();
+    let tables = [(b"abcd", stored, 6u32), (b"efgh", compressed, 64)];
+    let mut data = b"wOFF\0\x01\0\0".to_vec();
+    data.extend([0; 4]);
+    data.extend([0, 2, 0, 0, 0, 0, 0, 96]);
+    data.resize(44, 0);
+    let mut offset = 44 + 20 * tables.len();
+    for (tag, table, length) in &tables {
+      data.extend(*tag);
+      data.extend(u32::try_from(offset).unwrap().to_be_bytes());
+      data.extend(u32::try_from(table.len()).unwrap().to_be_bytes());
+      data.extend(length.to_be_bytes());
+      data.extend([0; 4]);
+      offset += table.len();
+    }
+    for (_, table, _) in tables {
+      data.extend(table);
+    }
+    data
+  }
+
+  #[test]
+  fn woff_unpacked() {
+    let sfnt = unpack_woff(&woff()).unwrap();
+    let face = RawFace::parse(&sfnt, 0).unwrap();
+    let table = |tag| face.table(ttf_parser::Tag::from_bytes(tag));
+    assert_eq!(table(b"abcd"), Some(&b"stored"[..]));
+    assert_eq!(table(b"efgh"), Some(&[7; 64][..]));
+  }
+
+  #[test]
+  fn woff_truncated() {
+    let data = woff();
+    for length in 0..data.len() {
+      assert!(unpack_woff(&data[..length]).is_none());
+    }
+    assert_eq!(unpack(&data[..20]), &data[..20]);
+  }
+}
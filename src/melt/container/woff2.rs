@@ -0,0 +1,682 @@
+/// WOFF2 files keep all tables in a single Brotli stream, `glyf` and `loca`
+/// possibly transformed into separate streams of contours, points, flags
+/// and so on, and `hmtx` with side bearings left out when they equal the
+/// `xMin` of the glyphs.
+/// - see: <https://www.w3.org/TR/WOFF2/#table_format>
+use super::{Font, Reader, Table, write_sfnt};
+use brotli_decompressor::Decompressor;
+use std::io::Read;
+
+/// Tags of the table directory known by their index in the flags.
+const KNOWN_TAGS: [&[u8; 4]; 63] = [
+  b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name", b"OS/2", b"post",
+  b"cvt ", b"fpgm", b"glyf", b"loca", b"prep", b"CFF ", b"VORG", b"EBDT",
+  b"EBLC", b"gasp", b"hdmx", b"kern", b"LTSH", b"PCLT", b"VDMX", b"vhea",
+  b"vmtx", b"BASE", b"GDEF", b"GPOS", b"GSUB", b"EBSC", b"JSTF", b"MATH",
+  b"CBDT", b"CBLC", b"COLR", b"CPAL", b"SVG ", b"sbix", b"acnt", b"avar",
+  b"bdat", b"bloc", b"bsln", b"cvar", b"fdsc", b"feat", b"fmtx", b"fvar",
+  b"gvar", b"hsty", b"just", b"lcar", b"mort", b"morx", b"opbd", b"prop",
+  b"trak", b"Zapf", b"Silf", b"Glat", b"Gloc", b"Feat", b"Sill",
+];
+
+// flags of composite glyph components
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+const WE_HAVE_INSTRUCTIONS: u16 = 0x0100;
+
+impl Reader<'_> {
+  /// `UIntBase128`, at most five bytes without leading zeros.
+  fn base128(&mut self) -> Option<u32> {
+    let mut value = 0u32;
+    for index in 0..5 {
+      let byte = self.u8()?;
+      if (index == 0 && byte == 0x80) || value.leading_zeros() < 7 {
+        return None;
+      }
+      value = (value << 7) | u32::from(byte & 0x7F);
+      if byte & 0x80 == 0 {
+        return Some(value);
+      }
+    }
+    None
+  }
+
+  /// `255UInt16`, one byte for small values.
+  fn u255(&mut self) -> Option<u16> {
+    match self.u8()? {
+      253 => self.u16(),
+      254 => Some(u16::from(self.u8()?) + 506),
+      255 => Some(u16::from(self.u8()?) + 253),
+      code => Some(u16::from(code)),
+    }
+  }
+}
+
+struct Entry {
+  tag: [u8; 4],
+  transformed: bool,
+  // length of the table once unpacked
+  original_length: usize,
+  // length of the table in the Brotli stream
+  length: usize,
+}
+
+impl Entry {
+  fn read(reader: &mut Reader) -> Option<Self> {
+    let flags = reader.u8()?;
+    let tag = match flags & 0x3F {
+      63 => reader.array()?,
+      index => *KNOWN_TAGS[usize::from(index)],
+    };
+    let version = flags >> 6;
+    // version 0 transforms `glyf` and `loca` and version 3 leaves them as
+    // they are, the other way round for other tables
+    let transformed = if &tag == b"glyf" || &tag == b"loca" {
+      version == 0
+    } else {
+      version != 0
+    };
+    if transformed && !matches!(&tag, b"glyf" | b"loca" | b"hmtx") {
+      return None;
+    }
+    let original_length = reader.base128()? as usize;
+    let length = if transformed {
+      reader.base128()? as usize
+    } else {
+      original_length
+    };
+    Some(Entry {
+      tag,
+      transformed,
+      original_length,
+      length,
+    })
+  }
+}
+
+/// `None` if the file is malformed or uses transforms not defined.
+pub(super) fn unpack(data: &[u8]) -> Option<Vec<u8>> {
+  let mut header = Reader::new(data);
+  header.u32()?;
+  let flavor = header.u32()?;
+  header.u32()?;
+  let num_tables = header.u16()?;
+  header.u16()?;
+  let sfnt_size = header.sfnt_size()?;
+  let compressed_length = header.u32()? as usize;
+  // the rest of the 48 byte header, e.g. the extended metadata block
+  header.bytes(24)?;
+  let entries = (0..num_tables)
+    .map(|_| Entry::read(&mut header))
+    .collect::<Option<Vec<Entry>>>()?;
+  // neither the tables nor the stream may be larger than the font
+  let (original_length, length) = entries.iter().try_fold(
+    (0usize, 0usize),
+    |(original, stream), entry| {
+      Some((
+        original.checked_add(entry.original_length)?,
+        stream.checked_add(entry.length)?,
+      ))
+    },
+  )?;
+  if original_length > sfnt_size || length > sfnt_size {
+    return None;
+  }
+  let collection = flavor == u32::from_be_bytes(*b"ttcf");
+  let fonts = if collection {
+    read_collection_directory(&mut header, entries.len())?
+  } else {
+    vec![Font {
+      flavor,
+      tables: (0..entries.len()).collect(),
+    }]
+  };
+  let compressed = header.bytes(compressed_length)?;
+  let mut stream = Vec::new();
+  Decompressor::new(compressed, 4096)
+    .take(length as u64)
+    .read_to_end(&mut stream)
+    .ok()?;
+  if stream.len() != length {
+    return None;
+  }
+  let mut stream = Reader::new(&stream);
+  let mut tables = Vec::with_capacity(entries.len());
+  let mut transformed = Vec::with_capacity(entries.len());
+  for entry in &entries {
+    let data = stream.bytes(entry.length)?;
+    tables.push(Table {
+      tag: entry.tag,
+      data: if entry.transformed {
+        Vec::new()
+      } else {
+        data.to_vec()
+      },
+    });
+    transformed.push(entry.transformed.then_some(data));
+  }
+  let mut x_mins = vec![None; entries.len()];
+  for font in &fonts {
+    reconstruct(font, &mut tables, &mut transformed, &mut x_mins)?;
+  }
+  write_sfnt(&fonts, &tables, collection)
+}
+
+fn read_collection_directory(
+  reader: &mut Reader,
+  num_tables: usize,
+) -> Option<Vec<Font>> {
+  reader.u32()?;
+  let num_fonts = reader.u255()?;
+  (0..num_fonts)
+    .map(|_| {
+      let num_font_tables = reader.u255()?;
+      let flavor = reader.u32()?;
+      let tables = (0..num_font_tables)
+        .map(|_| {
+          let index = usize::from(reader.u255()?);
+          (index < num_tables).then_some(index)
+        })
+        .collect::<Option<Vec<usize>>>()?;
+      Some(Font { flavor, tables })
+    })
+    .collect()
+}
+
+/// Reconstructs the transformed tables of a font. Fonts of a collection
+/// may share them, so a rebuilt table is taken out of `transformed` and not
+/// rebuilt again, the `xMin` of its glyphs are kept in `x_mins` at the index
+/// of `glyf` for the `hmtx` of later fonts.
+fn reconstruct(
+  font: &Font,
+  tables: &mut [Table],
+  transformed: &mut [Option<&[u8]>],
+  x_mins: &mut [Option<Vec<i16>>],
+) -> Option<()> {
+  let find = |tag: &[u8; 4]| {
+    font
+      .tables
+      .iter()
+      .copied()
+      .find(|&index| &tables[index].tag == tag)
+  };
+  let (glyf, loca, hhea, hmtx) =
+    (find(b"glyf"), find(b"loca"), find(b"hhea"), find(b"hmtx"));
+  if let Some(glyf) = glyf
+    && let Some(data) = transformed[glyf].take()
+  {
+    let loca = loca.filter(|&loca| transformed[loca].take().is_some())?;
+    let glyphs = Glyphs::reconstruct(data)?;
+    tables[glyf].data = glyphs.glyf;
+    tables[loca].data = glyphs.loca;
+    x_mins[glyf] = Some(glyphs.x_mins);
+  } else if loca.is_some_and(|loca| transformed[loca].is_some()) {
+    return None;
+  }
+  if let Some(hmtx) = hmtx
+    && let Some(data) = transformed[hmtx].take()
+  {
+    let hhea = &tables[hhea?].data;
+    let num_h_metrics = u16::from_be_bytes([*hhea.get(34)?, *hhea.get(35)?]);
+    tables[hmtx].data = reconstruct_hmtx(
+      data,
+      usize::from(num_h_metrics),
+      x_mins[glyf?].as_deref()?,
+    )?;
+  }
+  Some(())
+}
+
+/// `glyf` and `loca` rebuilt from the transformed `glyf`, with the `xMin`
+/// of each glyph for `hmtx`.
+struct Glyphs {
+  glyf: Vec<u8>,
+  loca: Vec<u8>,
+  x_mins: Vec<i16>,
+}
+
+impl Glyphs {
+  fn reconstruct(data: &[u8]) -> Option<Self> {
+    let mut header = Reader::new(data);
+    header.u16()?;
+    let option_flags = header.u16()?;
+    let num_glyphs = usize::from(header.u16()?);
+    let index_format = header.u16()?;
+    let mut lengths = [0usize; 7];
+    for length in &mut lengths {
+      *length = header.u32()? as usize;
+    }
+    let mut streams = Vec::with_capacity(lengths.len());
+    for length in lengths {
+      streams.push(Reader::new(header.bytes(length)?));
+    }
+    let [
+      mut contours,
+      mut points,
+      mut flags,
+      mut glyph_stream,
+      mut composites,
+      mut bboxes,
+      mut instructions,
+    ] = <[Reader; 7]>::try_from(streams).ok()?;
+    let bbox_bitmap = bboxes.bytes(4 * num_glyphs.div_ceil(32))?;
+    let overlap_bitmap = if option_flags & 1 == 0 {
+      None
+    } else {
+      Some(header.bytes(num_glyphs.div_ceil(8))?)
+    };
+    let is_set = |bitmap: &[u8], glyph: usize| {
+      bitmap[glyph / 8] & (0x80 >> (glyph % 8)) != 0
+    };
+    let mut glyphs = Glyphs {
+      glyf: Vec::new(),
+      loca: Vec::new(),
+      x_mins: Vec::with_capacity(num_glyphs),
+    };
+    let mut offsets = Vec::with_capacity(num_glyphs + 1);
+    for glyph in 0..num_glyphs {
+      offsets.push(glyphs.glyf.len());
+      let bbox = if is_set(bbox_bitmap, glyph) {
+        Some(bboxes.bytes(8)?)
+      } else {
+        None
+      };
+      let x_min = match contours.i16()? {
+        0 if bbox.is_some() => return None,
+        0 => 0,
+        -1 => glyphs.push_composite(
+          bbox?,
+          &mut composites,
+          &mut glyph_stream,
+          &mut instructions,
+        )?,
+        count if count > 0 => glyphs.push_simple(
+          count.unsigned_abs(),
+          bbox,
+          overlap_bitmap.is_some_and(|bitmap| is_set(bitmap, glyph)),
+          [
+            &mut points,
+            &mut flags,
+            &mut glyph_stream,
+            &mut instructions,
+          ],
+        )?,
+        _ => return None,
+      };
+      glyphs.x_mins.push(x_min);
+      glyphs.glyf.resize(glyphs.glyf.len().next_multiple_of(4), 0);
+    }
+    offsets.push(glyphs.glyf.len());
+    for offset in offsets {
+      if index_format == 0 {
+        let offset = u16::try_from(offset / 2).ok()?;
+        glyphs.loca.extend(offset.to_be_bytes());
+      } else {
+        let offset = u32::try_from(offset).ok()?;
+        glyphs.loca.extend(offset.to_be_bytes());
+      }
+    }
+    Some(glyphs)
+  }
+
+  /// Components are stored as in `glyf`, the number of instructions in the
+  /// glyph stream.
+  fn push_composite(
+    &mut self,
+    bbox: &[u8],
+    composites: &mut Reader,
+    glyph_stream: &mut Reader,
+    instructions: &mut Reader,
+  ) -> Option<i16> {
+    let mut components = composites.clone();
+    let mut has_instructions = false;
+    loop {
+      let flags = components.u16()?;
+      has_instructions |= flags & WE_HAVE_INSTRUCTIONS != 0;
+      let arguments = if flags & ARG_1_AND_2_ARE_WORDS == 0 {
+        2
+      } else {
+        4
+      };
+      let transform = if flags & WE_HAVE_A_SCALE != 0 {
+        2
+      } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+        4
+      } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+        8
+      } else {
+        0
+      };
+      components.bytes(2 + arguments + transform)?;
+      if flags & MORE_COMPONENTS == 0 {
+        break;
+      }
+    }
+    self.glyf.extend((-1i16).to_be_bytes());
+    self.glyf.extend(bbox);
+    self
+      .glyf
+      .extend(composites.bytes(components.offset - composites.offset)?);
+    if has_instructions {
+      let length = glyph_stream.u255()?;
+      self.glyf.extend(length.to_be_bytes());
+      self.glyf.extend(instructions.bytes(usize::from(length))?);
+    }
+    Some(i16::from_be_bytes([bbox[0], bbox[1]]))
+  }
+
+  /// Points are decoded from the flag and glyph streams, and written with
+  /// the shortest coordinates `glyf` allows.
+  fn push_simple(
+    &mut self,
+    num_contours: u16,
+    bbox: Option<&[u8]>,
+    overlap: bool,
+    [points, flags, glyph_stream, instructions]: [&mut Reader; 4],
+  ) -> Option<i16> {
+    let mut end_points = Vec::with_capacity(usize::from(num_contours));
+    let mut num_points = 0usize;
+    for _ in 0..num_contours {
+      num_points += usize::from(points.u255()?);
+      end_points.push(u16::try_from(num_points.checked_sub(1)?).ok()?);
+    }
+    let mut coordinates = Vec::with_capacity(num_points);
+    let (mut x, mut y) = (0i32, 0i32);
+    for &flag in flags.bytes(num_points)? {
+      let (on_curve, dx, dy) = triplet(flag, glyph_stream)?;
+      x += dx;
+      y += dy;
+      coordinates.push((
+        on_curve,
+        i16::try_from(x).ok()?,
+        i16::try_from(y).ok()?,
+      ));
+    }
+    let instruction_length = glyph_stream.u255()?;
+    let bbox = bbox.map_or_else(
+      || {
+        let xs = coordinates.iter().map(|&(_, x, _)| x);
+        let ys = coordinates.iter().map(|&(_, _, y)| y);
+        [xs.clone().min(), ys.clone().min(), xs.max(), ys.max()]
+          .into_iter()
+          .flat_map(|value| value.unwrap_or_default().to_be_bytes())
+          .collect()
+      },
+      <[u8]>::to_vec,
+    );
+    self.glyf.extend(num_contours.to_be_bytes());
+    self.glyf.extend(&bbox);
+    for end_point in end_points {
+      self.glyf.extend(end_point.to_be_bytes());
+    }
+    self.glyf.extend(instruction_length.to_be_bytes());
+    self
+      .glyf
+      .extend(instructions.bytes(usize::from(instruction_length))?);
+    let (mut point_flags, mut xs, mut ys) =
+      (Vec::new(), Vec::new(), Vec::new());
+    let (mut last_x, mut last_y) = (0i16, 0i16);
+    for (index, &(on_curve, x, y)) in coordinates.iter().enumerate() {
+      let mut flag = u8::from(on_curve);
+      if index == 0 && overlap {
+        flag |= 0x40;
+      }
+      flag |= push_delta(&mut xs, x.wrapping_sub(last_x), 0x02, 0x10);
+      flag |= push_delta(&mut ys, y.wrapping_sub(last_y), 0x04, 0x20);
+      point_flags.push(flag);
+      (last_x, last_y) = (x, y);
+    }
+    self.glyf.extend(point_flags);
+    self.glyf.extend(xs);
+    self.glyf.extend(ys);
+    Some(i16::from_be_bytes([bbox[0], bbox[1]]))
+  }
+}
+
+/// Writes a coordinate delta of a simple glyph, returns its flags: `short`
+/// for deltas fitting a byte, and `same` for zero or positive short ones.
+fn push_delta(
+  coordinates: &mut Vec<u8>,
+  delta: i16,
+  short: u8,
+  same: u8,
+) -> u8 {
+  if delta == 0 {
+    same
+  } else if let Ok(byte) = u8::try_from(delta.unsigned_abs()) {
+    coordinates.push(byte);
+    if delta > 0 { short | same } else { short }
+  } else {
+    coordinates.extend(delta.to_be_bytes());
+    0
+  }
+}
+
+/// Decodes a point of the glyph stream into whether it is on the curve and
+/// its offset from the previous point.
+fn triplet(flag: u8, glyph_stream: &mut Reader) -> Option<(bool, i32, i32)> {
+  let on_curve = flag & 0x80 == 0;
+  let flag = flag & 0x7F;
+  let with_sign = |flag: u8, value: i32| {
+    if flag & 1 == 1 { value } else { -value }
+  };
+  let mut byte = || glyph_stream.u8().map(i32::from);
+  let code = i32::from(flag);
+  let (dx, dy) = match flag {
+    0..10 => (0, with_sign(flag, ((code & 0x0E) << 7) + byte()?)),
+    10..20 => (with_sign(flag, (((code - 10) & 0x0E) << 7) + byte()?), 0),
+    20..84 => {
+      let (b0, b1) = (code - 20, byte()?);
+      (
+        with_sign(flag, 1 + (b0 & 0x30) + (b1 >> 4)),
+        with_sign(flag >> 1, 1 + ((b0 & 0x0C) << 2) + (b1 & 0x0F)),
+      )
+    }
+    84..120 => {
+      let (b0, b1, b2) = (code - 84, byte()?, byte()?);
+      (
+        with_sign(flag, 1 + ((b0 / 12) << 8) + b1),
+        with_sign(flag >> 1, 1 + (((b0 % 12) >> 2) << 8) + b2),
+      )
+    }
+    120..124 => {
+      let (b0, b1, b2) = (byte()?, byte()?, byte()?);
+      (
+        with_sign(flag, (b0 << 4) + (b1 >> 4)),
+        with_sign(flag >> 1, ((b1 & 0x0F) << 8) + b2),
+      )
+    }
+    _ => {
+      let (b0, b1, b2, b3) = (byte()?, byte()?, byte()?, byte()?);
+      (
+        with_sign(flag, (b0 << 8) + b1),
+        with_sign(flag >> 1, (b2 << 8) + b3),
+      )
+    }
+  };
+  Some((on_curve, dx, dy))
+}
+
+/// Side bearings left out are the `xMin` of the glyphs, flag bit 0 for
+/// those of glyphs with their own advance and bit 1 for the others.
+fn reconstruct_hmtx(
+  data: &[u8],
+  num_h_metrics: usize,
+  x_mins: &[i16],
+) -> Option<Vec<u8>> {
+  if num_h_metrics == 0 || num_h_metrics > x_mins.len() {
+    return None;
+  }
+  let mut reader = Reader::new(data);
+  let flags = reader.u8()?;
+  let advances = (0..num_h_metrics)
+    .map(|_| reader.u16())
+    .collect::<Option<Vec<u16>>>()?;
+  let mut hmtx = Vec::with_capacity(2 * (num_h_metrics + x_mins.len()));
+  for (glyph, &x_min) in x_mins.iter().enumerate() {
+    let omitted = if glyph < num_h_metrics {
+      flags & 1 != 0
+    } else {
+      flags & 2 != 0
+    };
+    let side_bearing = if omitted { x_min } else { reader.i16()? };
+    if let Some(advance) = advances.get(glyph) {
+      hmtx.extend(advance.to_be_bytes());
+    }
+    hmtx.extend(side_bearing.to_be_bytes());
+  }
+  Some(hmtx)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn base128(bytes: &[u8]) -> Option<u32> {
+    Reader::new(bytes).base128()
+  }
+
+  fn u255(bytes: &[u8]) -> Option<u16> {
+    Reader::new(bytes).u255()
+  }
+
+  fn triplet(flag: u8, bytes: &[u8]) -> Option<(bool, i32, i32)> {
+    let mut reader = Reader::new(bytes);
+    let point = super::triplet(flag, &mut reader)?;
+    (reader.offset == bytes.len()).then_some(point)
+  }
+
+  #[test]
+  fn base128_values() {
+    assert_eq!(base128(&[0x3F]), Some(63));
+    assert_eq!(base128(&[0x81, 0x00]), Some(128));
+    assert_eq!(base128(&[0x8F, 0xFF, 0xFF, 0xFF, 0x7F]), Some(u32::MAX));
+  }
+
+  #[test]
+  fn base128_rejected() {
+    // leading zeros
+    assert_eq!(base128(&[0x80, 0x01]), None);
+    assert_eq!(base128(&[0x80]), None);
+    // more than five bytes
+    assert_eq!(base128(&[0x81, 0x80, 0x80, 0x80, 0x80, 0x00]), None);
+    // more than 32 bits
+    assert_eq!(base128(&[0x90, 0x80, 0x80, 0x80, 0x00]), None);
+    // no last byte
+    assert_eq!(base128(&[0x81, 0x80]), None);
+  }
+
+  #[test]
+  fn u255_values() {
+    assert_eq!(u255(&[0]), Some(0));
+    assert_eq!(u255(&[252]), Some(252));
+    assert_eq!(u255(&[255, 0]), Some(253));
+    assert_eq!(u255(&[255, 252]), Some(505));
+    assert_eq!(u255(&[254, 0]), Some(506));
+    assert_eq!(u255(&[253, 0x01, 0xFA]), Some(506));
+    assert_eq!(u255(&[253, 0xFF, 0xFF]), Some(u16::MAX));
+    assert_eq!(u255(&[253, 0x01]), None);
+    assert_eq!(u255(&[254]), None);
+  }
+
+  #[test]
+  fn triplet_table() {
+    // y only, 8 bits
+    assert_eq!(triplet(0, &[5]), Some((true, 0, -5)));
+    assert_eq!(triplet(3, &[5]), Some((true, 0, 261)));
+    assert_eq!(triplet(9, &[0xFF]), Some((true, 0, 1279)));
+    // x only, 8 bits
+    assert_eq!(triplet(10, &[5]), Some((true, -5, 0)));
+    assert_eq!(triplet(13, &[5]), Some((true, 261, 0)));
+    // 4 bits each
+    assert_eq!(triplet(20, &[0x23]), Some((true, -3, -4)));
+    assert_eq!(triplet(21, &[0x23]), Some((true, 3, -4)));
+    assert_eq!(triplet(22, &[0x23]), Some((true, -3, 4)));
+    assert_eq!(triplet(23, &[0x23]), Some((true, 3, 4)));
+    assert_eq!(triplet(36, &[0x00]), Some((true, -17, -1)));
+    assert_eq!(triplet(83, &[0xFF]), Some((true, 64, 64)));
+    // 8 bits each
+    assert_eq!(triplet(84, &[1, 2]), Some((true, -2, -3)));
+    assert_eq!(triplet(99, &[0, 0]), Some((true, 257, 1)));
+    assert_eq!(triplet(119, &[0xFF, 0xFF]), Some((true, 768, 768)));
+    // 12 bits each
+    assert_eq!(triplet(120, &[0x12, 0x34, 0x56]), Some((true, -291, -1110)));
+    assert_eq!(triplet(123, &[0x12, 0x34, 0x56]), Some((true, 291, 1110)));
+    // 16 bits each
+    assert_eq!(triplet(124, &[1, 0, 2, 0]), Some((true, -256, -512)));
+    assert_eq!(triplet(127, &[1, 0, 2, 0]), Some((true, 256, 512)));
+    // the top bit marks points off the curve
+    assert_eq!(triplet(0x80 | 0x54, &[1, 2]), Some((false, -2, -3)));
+    assert_eq!(triplet(84, &[1]), None);
+  }
+
+  /// A transformed `glyf` of an empty glyph, a triangle and a composite of
+  /// the triangle with its bounding box.
+  fn transformed_glyf() -> Vec<u8> {
+    let streams: [&[u8]; 7] = [
+      &[0, 0, 0, 1, 0xFF, 0xFF],
+      &[3],
+      &[11, 11, 86],
+      &[10, 100, 49, 99, 0],
+      &[0, 1, 0, 1, 0, 5, 0, 6],
+      &[0x20, 0, 0, 0, 0, 0, 0, 0, 0, 110, 0, 100],
+      &[],
+    ];
+    let mut data = vec![0, 0, 0, 0, 0, 3, 0, 0];
+    for stream in streams {
+      data.extend(u32::try_from(stream.len()).unwrap().to_be_bytes());
+    }
+    data.extend(streams.concat());
+    data
+  }
+
+  #[test]
+  fn glyf_round_trip() {
+    let glyphs = Glyphs::reconstruct(&transformed_glyf()).unwrap();
+    let glyf: &[u8] = &[
+      // triangle
+      0, 1, 0, 10, 0, 0, 0, 110, 0, 100, 0, 2, 0, 0, 0x33, 0x33, 0x27, 10,
+      100, 50, 100, 0, 0, 0, // composite
+      0xFF, 0xFF, 0, 0, 0, 0, 0, 110, 0, 100, 0, 1, 0, 1, 0, 5, 0, 6, 0, 0,
+    ];
+    assert_eq!(glyphs.glyf, glyf);
+    assert_eq!(glyphs.loca, [0, 0, 0, 0, 0, 12, 0, 22]);
+    assert_eq!(glyphs.x_mins, [0, 10, 0]);
+  }
+
+  #[test]
+  fn glyf_truncated() {
+    let data = transformed_glyf();
+    for length in 0..data.len() {
+      assert!(Glyphs::reconstruct(&data[..length]).is_none());
+    }
+  }
+
+  #[test]
+  fn hmtx_round_trip() {
+    let x_mins = [0, 10, 0];
+    let hmtx = [1, 244, 0, 0, 2, 88, 0, 10, 0, 0];
+    let omitted = [3, 1, 244, 2, 88];
+    assert_eq!(reconstruct_hmtx(&omitted, 2, &x_mins).unwrap(), hmtx);
+    let explicit = [0, 1, 244, 2, 88, 0, 0, 0, 10, 0, 0];
+    assert_eq!(reconstruct_hmtx(&explicit, 2, &x_mins).unwrap(), hmtx);
+    let mixed = [2, 1, 244, 2, 88, 0, 0, 0, 10];
+    assert_eq!(reconstruct_hmtx(&mixed, 2, &x_mins).unwrap(), hmtx);
+    assert!(reconstruct_hmtx(&explicit[..10], 2, &x_mins).is_none());
+    assert!(reconstruct_hmtx(&omitted, 4, &x_mins).is_none());
+  }
+
+  #[test]
+  fn header_truncated() {
+    let mut data = b"wOF2\0\x01\0\0".to_vec();
+    data.extend([0, 0, 0, 48, 0, 0, 0, 0, 0, 0, 0, 12, 0, 0, 0, 0]);
+    data.resize(48, 0);
+    assert!(unpack(&data).is_some_and(|sfnt| sfnt.len() == 12));
+    for length in 0..data.len() {
+      assert!(unpack(&data[..length]).is_none());
+    }
+  }
+}
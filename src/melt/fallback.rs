@@ -0,0 +1,102 @@
+/// This module resolves which font of an ordered font list renders each
+/// grapheme cluster of a string, following Typst's family fallback rules.
+use super::repr::FontRepr;
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Whether the character is a `Default_Ignorable_Code_Point`.
+///
+/// Typst drops such characters instead of rendering tofu, so they never
+/// force a fallback on their own.
+///
+/// From: <https://www.unicode.org/Public/UCD/latest/ucd/DerivedCoreProperties.txt>
+fn is_default_ignorable(ch: char) -> bool {
+  matches!(
+    ch,
+    '\u{00AD}'
+      | '\u{034F}'
+      | '\u{061C}'
+      | '\u{115F}'..='\u{1160}'
+      | '\u{17B4}'..='\u{17B5}'
+      | '\u{180B}'..='\u{180F}'
+      | '\u{200B}'..='\u{200F}'
+      | '\u{202A}'..='\u{202E}'
+      | '\u{2060}'..='\u{206F}'
+      | '\u{3164}'
+      | '\u{FE00}'..='\u{FE0F}'
+      | '\u{FEFF}'
+      | '\u{FFA0}'
+      | '\u{FFF0}'..='\u{FFF8}'
+      | '\u{1BCA0}'..='\u{1BCA3}'
+      | '\u{1D173}'..='\u{1D17A}'
+      | '\u{E0000}'..='\u{E0FFF}'
+  )
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ClusterFallback {
+  text: String,
+  // byte range of the cluster in the input string
+  start: usize,
+  end: usize,
+  // index into the font list, `None` if no font covers the cluster
+  font: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct FontsFallback {
+  clusters: Vec<ClusterFallback>,
+  // indices of clusters that will be rendered as tofu
+  uncovered: Vec<usize>,
+}
+
+impl FontsFallback {
+  /// Like Typst, a font is only selected for a cluster if it covers every
+  /// non-ignorable character of it, otherwise the next font is tried.
+  fn covers(repr: &FontRepr, cluster: &str) -> bool {
+    repr.info().is_some_and(|info| {
+      cluster
+        .chars()
+        .filter(|&ch| !is_default_ignorable(ch))
+        .all(|ch| info.coverage.contains(u32::from(ch)))
+    })
+  }
+
+  pub(crate) fn from_reprs(text: &str, reprs: &[Option<FontRepr>]) -> Self {
+    // the first font that could be read, for ignorables opening the text
+    let primary = reprs.iter().position(Option::is_some);
+    let mut previous = None;
+    let clusters: Vec<ClusterFallback> = text
+      .grapheme_indices(true)
+      .map(|(start, cluster)| {
+        let font = if cluster.chars().all(is_default_ignorable) {
+          // nothing visible, keep it with the font of the preceding text
+          previous.or(primary)
+        } else {
+          reprs.iter().position(|repr| {
+            repr
+              .as_ref()
+              .is_some_and(|repr| Self::covers(repr, cluster))
+          })
+        };
+        previous = font.or(previous);
+        ClusterFallback {
+          text: cluster.to_string(),
+          start,
+          end: start + cluster.len(),
+          font,
+        }
+      })
+      .collect();
+    let uncovered = clusters
+      .iter()
+      .enumerate()
+      .filter(|(_, cluster)| cluster.font.is_none())
+      .map(|(id, _)| id)
+      .collect();
+    FontsFallback {
+      clusters,
+      uncovered,
+    }
+  }
+}
@@ -0,0 +1,309 @@
+/// This module rasterizes glyphs and shaped strings into anti-aliased PNG
+/// bitmaps with tiny-skia, so that pixels do not depend on the renderer of
+/// the viewer.
+use super::repr::FontRepr;
+use super::shaping::{ShapedText, ShapingOptions};
+use super::ttf::glyphs::GlyphInfo;
+use super::ttf::hinting::{Hinter, HintingOptions};
+use super::ttf::outline::Outline;
+use serde::{Deserialize, Serialize};
+use serde_bytes::ByteBuf;
+use tiny_skia::{
+  Color, FillRule, Mask, Paint, PathBuilder, Pixmap, Rect, Transform,
+};
+use ttf_parser::{GlyphId, OutlineBuilder};
+
+// larger sizes are rejected, a glyph of one em then fits in `MAX_PIXELS`
+const MAX_PPEM: f32 = 4096.0;
+// larger bitmaps are rejected rather than allocated, so both sides are
+// below 2^24 and exact as `f32`
+const MAX_PIXELS: u64 = 1 << 24;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum PixelFormat {
+  // coverage only, 0 is background and 255 is full ink, like FreeType
+  #[default]
+  Gray,
+  // `color` composed over `background`
+  Rgba,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct RasterOptions {
+  // pixels per em
+  pub(crate) ppem: f32,
+  // subpixel offset of the origin in pixels, y axis points up
+  pub(crate) offset: [f32; 2],
+  pub(crate) format: PixelFormat,
+  // ink color of `rgba` bitmaps
+  pub(crate) color: [u8; 4],
+  // background of `rgba` bitmaps, transparent if not given
+  pub(crate) background: Option<[u8; 4]>,
+  // hint outlines before rasterizing, its `ppem` is replaced by ours
+  pub(crate) hinting: Option<HintingOptions>,
+}
+
+impl Default for RasterOptions {
+  fn default() -> Self {
+    RasterOptions {
+      ppem: 16.0,
+      offset: [0.0, 0.0],
+      format: PixelFormat::default(),
+      color: [0, 0, 0, 255],
+      background: None,
+      hinting: None,
+    }
+  }
+}
+
+/// Scales or hints outlines of one font to pixels.
+struct Scaler<'r, 'a> {
+  repr: &'r FontRepr<'a>,
+  // pixels per font unit
+  scale: f32,
+  hinter: Option<Hinter<'a>>,
+}
+
+impl<'r, 'a> Scaler<'r, 'a> {
+  fn new(repr: &'r FontRepr<'a>, options: &RasterOptions) -> Option<Self> {
+    if !(options.ppem > 0.0 && options.ppem <= MAX_PPEM) {
+      return None;
+    }
+    let hinter = match &options.hinting {
+      Some(hinting) => Some(Hinter::new(
+        repr,
+        &HintingOptions {
+          ppem: options.ppem,
+          ..hinting.clone()
+        },
+      )?),
+      None => None,
+    };
+    Some(Scaler {
+      repr,
+      scale: options.ppem / f32::from(repr.ttf_parser.units_per_em()),
+      hinter,
+    })
+  }
+
+  /// Outline in pixels and the advance (hinted if hinting is on).
+  fn glyph(&self, glyph_id: GlyphId) -> (Outline, Option<f32>) {
+    if let Some(hinter) = &self.hinter
+      && let Some(glyph) = hinter.hint(glyph_id)
+    {
+      return (glyph.outline, glyph.advance);
+    }
+    let mut outline =
+      Outline::from_glyph_id(self.repr, glyph_id).unwrap_or_default();
+    outline.scale(self.scale);
+    let advance = self
+      .repr
+      .ttf_parser
+      .glyph_hor_advance(glyph_id)
+      .map(|advance| f32::from(advance) * self.scale);
+    (outline, advance)
+  }
+}
+
+/// Writes outlines into a tiny-skia path, flipping the y axis and moving
+/// the top left corner of the bitmap to the origin.
+struct PathWriter {
+  builder: PathBuilder,
+  left: f32,
+  top: f32,
+}
+
+impl OutlineBuilder for PathWriter {
+  fn move_to(&mut self, x: f32, y: f32) {
+    self.builder.move_to(x - self.left, self.top - y);
+  }
+
+  fn line_to(&mut self, x: f32, y: f32) {
+    self.builder.line_to(x - self.left, self.top - y);
+  }
+
+  fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+    self.builder.quad_to(
+      x1 - self.left,
+      self.top - y1,
+      x - self.left,
+      self.top - y,
+    );
+  }
+
+  fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+    self.builder.cubic_to(
+      x1 - self.left,
+      self.top - y1,
+      x2 - self.left,
+      self.top - y2,
+      x - self.left,
+      self.top - y,
+    );
+  }
+
+  fn close(&mut self) {
+    self.builder.close();
+  }
+}
+
+/// A rendered bitmap, `left` and `top` are the offsets of its left and top
+/// edges from the origin in pixels with y axis pointing up, like
+/// `bitmap_left` and `bitmap_top` of `FreeType`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Bitmap {
+  // PNG data, `None` if nothing is inked (e.g. space)
+  png: Option<ByteBuf>,
+  width: u32,
+  height: u32,
+  left: i32,
+  top: i32,
+  // pen advance in pixels along the writing direction
+  advance: f32,
+}
+
+impl Bitmap {
+  fn gray_png(mask: &Mask) -> Option<Vec<u8>> {
+    let mut data = Vec::new();
+    let mut encoder =
+      png::Encoder::new(&mut data, mask.width(), mask.height());
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().ok()?;
+    writer.write_image_data(mask.data()).ok()?;
+    writer.finish().ok()?;
+    Some(data)
+  }
+
+  fn rgba_png(mask: &Mask, options: &RasterOptions) -> Option<Vec<u8>> {
+    let mut pixmap = Pixmap::new(mask.width(), mask.height())?;
+    if let Some([r, g, b, a]) = options.background {
+      pixmap.fill(Color::from_rgba8(r, g, b, a));
+    }
+    let [r, g, b, a] = options.color;
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(r, g, b, a);
+    #[allow(clippy::cast_precision_loss)]
+    let rect =
+      Rect::from_xywh(0.0, 0.0, mask.width() as f32, mask.height() as f32)?;
+    pixmap.fill_rect(rect, &paint, Transform::identity(), Some(mask));
+    pixmap.encode_png().ok()
+  }
+
+  /// Renders an outline in pixels, the origin is moved by the subpixel
+  /// offset of `options` first.
+  #[allow(clippy::cast_possible_truncation)]
+  fn render(
+    mut outline: Outline,
+    advance: f32,
+    options: &RasterOptions,
+  ) -> Option<Self> {
+    let [dx, dy] = options.offset;
+    outline.translate(dx, dy);
+    let Some(bbox) = outline.bbox() else {
+      return Some(Bitmap {
+        png: None,
+        width: 0,
+        height: 0,
+        left: 0,
+        top: 0,
+        advance,
+      });
+    };
+    let (left, top) = (bbox.x_min.floor(), bbox.y_max.ceil());
+    let width = u32::try_from((bbox.x_max.ceil() - left) as i64).ok()?;
+    let height = u32::try_from((top - bbox.y_min.floor()) as i64).ok()?;
+    if u64::from(width.max(1)) * u64::from(height.max(1)) > MAX_PIXELS {
+      return None;
+    }
+    let mut writer = PathWriter {
+      builder: PathBuilder::new(),
+      left,
+      top,
+    };
+    outline.replay(&mut writer);
+    let mut mask = Mask::new(width.max(1), height.max(1))?;
+    if let Some(path) = writer.builder.finish() {
+      mask.fill_path(&path, FillRule::Winding, true, Transform::identity());
+    }
+    let png = match options.format {
+      PixelFormat::Gray => Self::gray_png(&mask)?,
+      PixelFormat::Rgba => Self::rgba_png(&mask, options)?,
+    };
+    Some(Bitmap {
+      png: Some(ByteBuf::from(png)),
+      width: mask.width(),
+      height: mask.height(),
+      left: left as i32,
+      top: top as i32,
+      advance,
+    })
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct GlyphsBitmaps(Vec<Option<Bitmap>>);
+
+impl GlyphsBitmaps {
+  pub(crate) fn from_option_iter(
+    repr: &FontRepr,
+    codes: impl Iterator<Item = Option<char>>,
+    options: &RasterOptions,
+  ) -> Option<Self> {
+    let scaler = Scaler::new(repr, options)?;
+    let bitmaps = codes.map(|code| {
+      let glyph = GlyphInfo::from_character(repr, code?)?;
+      let (outline, advance) = scaler.glyph(glyph.id());
+      Bitmap::render(outline, advance.unwrap_or_default(), options)
+    });
+    Some(GlyphsBitmaps(bitmaps.collect()))
+  }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct TextRasterOptions {
+  pub(crate) raster: RasterOptions,
+  // `size` is ignored, `raster.ppem` is used instead
+  pub(crate) shaping: ShapingOptions,
+}
+
+impl Bitmap {
+  /// Glyphs are placed at the positions of the shaper, rounded to whole
+  /// pixels if hinting is on so that hinted stems stay on the grid.
+  pub(crate) fn from_text(
+    repr: &FontRepr,
+    text: &str,
+    options: &TextRasterOptions,
+  ) -> Option<Self> {
+    let scaler = Scaler::new(repr, &options.raster)?;
+    let shaped = ShapedText::shape(repr, text, &options.shaping);
+    let snap = |value: f32| {
+      if scaler.hinter.is_some() {
+        value.round()
+      } else {
+        value
+      }
+    };
+    let mut outline = Outline::default();
+    #[allow(clippy::cast_precision_loss)]
+    for (glyph, (x, y)) in shaped.glyphs.iter().zip(shaped.positions()) {
+      let (mut glyph_outline, _) = scaler.glyph(glyph.id);
+      glyph_outline.translate(
+        snap(x as f32 * scaler.scale),
+        snap(y as f32 * scaler.scale),
+      );
+      outline.append(glyph_outline);
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let advance = shaped
+      .glyphs
+      .iter()
+      .map(|glyph| shaped.advance_of(glyph))
+      .sum::<i32>() as f32
+      * scaler.scale;
+    Bitmap::render(outline, advance, &options.raster)
+  }
+}
@@ -1,4 +1,8 @@
+use super::container;
+use super::ttf::cff::CidCharset;
 use skrifa::FontRef;
+use std::borrow::Cow;
+use std::cell::OnceCell;
 use ttf_parser::Face;
 use typst_library::text;
 
@@ -7,29 +11,65 @@ pub(crate) struct FontRepr<'a> {
   pub(crate) ttf_parser: Face<'a>,
   #[allow(dead_code)]
   pub(crate) font_ref: FontRef<'a>,
-  pub(crate) info: text::FontInfo,
   pub(crate) metrics: text::FontMetrics,
-  #[allow(dead_code)]
+  // Typst computes the full coverage, so it is only built on demand.
+  info: OnceCell<Option<text::FontInfo>>,
+  // the charset of CID-keyed `CFF ` fonts, read on the first glyph lookup
+  cid_charset: OnceCell<Option<CidCharset<'a>>>,
   data: &'a [u8],
-  #[allow(dead_code)]
   index: u32,
 }
 
 impl<'a> FontRepr<'a> {
   pub(crate) fn new(data: &'a [u8], index: u32) -> Option<Self> {
     let ttf = Face::parse(data, index).ok()?;
-    // The below line shall never encounter additional error if
-    // ttf parsing succeeds.
-    let info = text::FontInfo::new(data, index).unwrap();
     let metrics = text::FontMetrics::from_ttf(&ttf);
     let font_ref = FontRef::from_index(data, index).ok()?;
     Some(FontRepr {
       ttf_parser: ttf,
       font_ref,
-      info,
       metrics,
+      info: OnceCell::new(),
+      cid_charset: OnceCell::new(),
       data,
       index,
     })
   }
+
+  /// Typst's view of the font, `None` if Typst cannot read it.
+  pub(crate) fn info(&self) -> Option<&text::FontInfo> {
+    self
+      .info
+      .get_or_init(|| text::FontInfo::new(self.data, self.index))
+      .as_ref()
+  }
+
+  /// Typst's view of the font with an empty coverage, for its family,
+  /// variant and flags. Unless already computed, Typst reads a copy of the
+  /// font without `cmap`, so the coverage is not built.
+  pub(crate) fn info_without_coverage(
+    &self,
+  ) -> Option<Cow<'_, text::FontInfo>> {
+    if let Some(info) = self.info.get() {
+      return info.as_ref().map(Cow::Borrowed);
+    }
+    // the tables Typst reads names, styles and flags from
+    let data = container::with_tables(
+      self.ttf_parser.raw_face(),
+      &[
+        b"head", b"hhea", b"maxp", b"name", b"OS/2", b"post", b"fvar",
+        b"MATH",
+      ],
+    )?;
+    text::FontInfo::new(&data, 0).map(Cow::Owned)
+  }
+
+  /// The charset mapping glyphs to CIDs, `None` unless the font is a
+  /// CID-keyed `CFF ` font.
+  pub(crate) fn cid_charset(&self) -> Option<&CidCharset<'a>> {
+    self
+      .cid_charset
+      .get_or_init(|| CidCharset::new(&self.font_ref))
+      .as_ref()
+  }
 }
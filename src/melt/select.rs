@@ -0,0 +1,73 @@
+/// This module selects which fields of `FontInfo` are computed and
+/// serialized, from dot separated paths like `typst.info.family`.
+use serde::{Deserialize, Serialize};
+use serde_cbor::Value;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FieldSelector(Option<Vec<Vec<String>>>);
+
+impl FieldSelector {
+  /// `None` selects every field.
+  pub(crate) fn new(fields: Option<Vec<String>>) -> Self {
+    FieldSelector(fields.map(|fields| {
+      fields
+        .iter()
+        .map(|field| field.split('.').map(String::from).collect())
+        .collect()
+    }))
+  }
+
+  /// Whether any field of the section is selected, i.e., some selected path
+  /// is a prefix of the section, or the section is a prefix of it.
+  pub(crate) fn wants(&self, section: &str) -> bool {
+    let Some(paths) = &self.0 else {
+      return true;
+    };
+    let section: Vec<&str> = section.split('.').collect();
+    paths.iter().any(|path| {
+      path
+        .iter()
+        .zip(section.iter())
+        .all(|(field, section)| field == section)
+    })
+  }
+
+  fn prune_with(value: Value, paths: &[&[String]]) -> Value {
+    // a path ended above, keep the whole subtree
+    if paths.iter().any(|path| path.is_empty()) {
+      return value;
+    }
+    match value {
+      Value::Map(map) => Value::Map(
+        map
+          .into_iter()
+          .filter_map(|(key, value)| {
+            let Value::Text(name) = &key else {
+              return None;
+            };
+            let tails: Vec<&[String]> = paths
+              .iter()
+              .filter(|path| path[0] == *name)
+              .map(|path| &path[1..])
+              .collect();
+            (!tails.is_empty())
+              .then(|| (key, Self::prune_with(value, &tails)))
+          })
+          .collect(),
+      ),
+      // selecting inside of a non-map value is meaningless, keep it
+      value => value,
+    }
+  }
+
+  /// Drops every field of `value` that is not selected.
+  pub(crate) fn prune(&self, value: Value) -> Value {
+    match &self.0 {
+      None => value,
+      Some(paths) => {
+        let paths: Vec<&[String]> = paths.iter().map(Vec::as_slice).collect();
+        Self::prune_with(value, &paths)
+      }
+    }
+  }
+}
@@ -0,0 +1,298 @@
+/// This module shapes and measures strings with a single font.
+use super::repr::FontRepr;
+use super::ttf::glyphs::BBox;
+use rustybuzz::{Direction, Feature, Language, Script, UnicodeBuffer};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use ttf_parser::{GlyphId, Tag};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct ShapingOptions {
+  // font size in pt
+  pub(crate) size: f64,
+  // apply OpenType shaping (GSUB/GPOS), otherwise map characters through
+  // `cmap` one by one
+  pub(crate) shaping: bool,
+  pub(crate) kerning: bool,
+  // features in harfbuzz syntax, e.g. `liga=0`, `+ss01`, `-calt`
+  pub(crate) features: Vec<String>,
+  // one of `ltr`, `rtl`, `ttb`, `btt`, guessed from text if not given
+  pub(crate) direction: Option<String>,
+  // ISO 15924 script tag, guessed from text if not given
+  pub(crate) script: Option<String>,
+  // BCP-47 language tag
+  pub(crate) language: Option<String>,
+}
+
+impl Default for ShapingOptions {
+  fn default() -> Self {
+    ShapingOptions {
+      size: 11.0,
+      shaping: true,
+      kerning: true,
+      features: Vec::new(),
+      direction: None,
+      script: None,
+      language: None,
+    }
+  }
+}
+
+/// A positioned glyph, all quantities are in font units.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ShapedGlyph {
+  pub(crate) id: GlyphId,
+  // byte offset of the cluster start in the shaped text
+  pub(crate) cluster: usize,
+  pub(crate) x_advance: i32,
+  pub(crate) y_advance: i32,
+  pub(crate) x_offset: i32,
+  pub(crate) y_offset: i32,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ShapedText {
+  pub(crate) glyphs: Vec<ShapedGlyph>,
+  pub(crate) vertical: bool,
+}
+
+impl ShapedText {
+  fn direction(options: &ShapingOptions) -> Option<Direction> {
+    options
+      .direction
+      .as_deref()
+      .and_then(|dir| Direction::from_str(dir).ok())
+  }
+
+  fn shape_opentype(
+    repr: &FontRepr,
+    text: &str,
+    options: &ShapingOptions,
+  ) -> Self {
+    let face = rustybuzz::Face::from_face(repr.ttf_parser.clone());
+    let mut buffer = UnicodeBuffer::new();
+    buffer.push_str(text);
+    if let Some(direction) = Self::direction(options) {
+      buffer.set_direction(direction);
+    }
+    if let Some(script) = options
+      .script
+      .as_deref()
+      .and_then(|script| Script::from_str(script).ok())
+    {
+      buffer.set_script(script);
+    }
+    if let Some(language) = options
+      .language
+      .as_deref()
+      .and_then(|lang| Language::from_str(lang).ok())
+    {
+      buffer.set_language(language);
+    }
+    buffer.guess_segment_properties();
+    let vertical = matches!(
+      buffer.direction(),
+      Direction::TopToBottom | Direction::BottomToTop
+    );
+
+    let mut features: Vec<Feature> = options
+      .features
+      .iter()
+      .filter_map(|feat| Feature::from_str(feat).ok())
+      .collect();
+    if !options.kerning {
+      features.push(Feature::new(Tag::from_bytes(b"kern"), 0, ..));
+    }
+
+    let output = rustybuzz::shape(&face, &features, buffer);
+    let glyphs = output
+      .glyph_infos()
+      .iter()
+      .zip(output.glyph_positions())
+      .map(|(info, pos)| ShapedGlyph {
+        id: GlyphId(u16::try_from(info.glyph_id).unwrap_or_default()),
+        cluster: info.cluster as usize,
+        x_advance: pos.x_advance,
+        y_advance: pos.y_advance,
+        x_offset: pos.x_offset,
+        y_offset: pos.y_offset,
+      })
+      .collect();
+    ShapedText { glyphs, vertical }
+  }
+
+  /// Maps characters one by one, only the legacy `kern` table is applied
+  /// for kerning.
+  fn shape_simple(
+    repr: &FontRepr,
+    text: &str,
+    options: &ShapingOptions,
+  ) -> Self {
+    let ttf = &repr.ttf_parser;
+    let vertical = matches!(
+      Self::direction(options),
+      Some(Direction::TopToBottom | Direction::BottomToTop)
+    );
+    let mut glyphs: Vec<ShapedGlyph> = text
+      .char_indices()
+      .map(|(cluster, ch)| {
+        let id = ttf.glyph_index(ch).unwrap_or(GlyphId(0));
+        let (x_advance, y_advance) = if vertical {
+          (0, -i32::from(ttf.glyph_ver_advance(id).unwrap_or_default()))
+        } else {
+          (i32::from(ttf.glyph_hor_advance(id).unwrap_or_default()), 0)
+        };
+        ShapedGlyph {
+          id,
+          cluster,
+          x_advance,
+          y_advance,
+          x_offset: 0,
+          y_offset: 0,
+        }
+      })
+      .collect();
+
+    if options.kerning
+      && !vertical
+      && let Some(kern) = ttf.tables().kern
+    {
+      for index in 1..glyphs.len() {
+        let (left, right) = (glyphs[index - 1].id, glyphs[index].id);
+        let adjust: i32 = kern
+          .subtables
+          .into_iter()
+          .filter(|table| table.horizontal && !table.variable)
+          .filter_map(|table| table.glyphs_kerning(left, right))
+          .map(i32::from)
+          .sum();
+        glyphs[index - 1].x_advance += adjust;
+      }
+    }
+    ShapedText { glyphs, vertical }
+  }
+
+  pub(crate) fn shape(
+    repr: &FontRepr,
+    text: &str,
+    options: &ShapingOptions,
+  ) -> Self {
+    if options.shaping {
+      Self::shape_opentype(repr, text, options)
+    } else {
+      Self::shape_simple(repr, text, options)
+    }
+  }
+
+  /// Pen positions (in font units) of every glyph, offsets applied.
+  pub(crate) fn positions(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+    self.glyphs.iter().scan((0, 0), |pen, glyph| {
+      let position = (pen.0 + glyph.x_offset, pen.1 + glyph.y_offset);
+      pen.0 += glyph.x_advance;
+      pen.1 += glyph.y_advance;
+      Some(position)
+    })
+  }
+
+  /// Advance of a glyph along the writing direction.
+  pub(crate) fn advance_of(&self, glyph: &ShapedGlyph) -> i32 {
+    if self.vertical {
+      -glyph.y_advance
+    } else {
+      glyph.x_advance
+    }
+  }
+
+  /// Union of glyph bounding boxes in font units, y axis points up.
+  pub(crate) fn ink_bbox(&self, repr: &FontRepr) -> Option<BBox<i32>> {
+    let ttf = &repr.ttf_parser;
+    self
+      .glyphs
+      .iter()
+      .zip(self.positions())
+      .filter_map(|(glyph, (x, y))| {
+        let rect = ttf.glyph_bounding_box(glyph.id)?;
+        Some(BBox {
+          x_min: x + i32::from(rect.x_min),
+          y_min: y + i32::from(rect.y_min),
+          x_max: x + i32::from(rect.x_max),
+          y_max: y + i32::from(rect.y_max),
+        })
+      })
+      .reduce(|acc, bbox| BBox {
+        x_min: acc.x_min.min(bbox.x_min),
+        y_min: acc.y_min.min(bbox.y_min),
+        x_max: acc.x_max.max(bbox.x_max),
+        y_max: acc.y_max.max(bbox.y_max),
+      })
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ClusterAdvance {
+  text: String,
+  // byte range of the cluster in the input string
+  start: usize,
+  end: usize,
+  advance: f64,
+}
+
+/// Measurement of a string, all quantities are in pt.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct TextMeasure {
+  advance: f64,
+  bbox: Option<BBox<f64>>,
+  clusters: Vec<ClusterAdvance>,
+}
+
+impl TextMeasure {
+  pub(crate) fn from_repr(
+    repr: &FontRepr,
+    text: &str,
+    options: &ShapingOptions,
+  ) -> Self {
+    let shaped = ShapedText::shape(repr, text, options);
+    let scale = options.size / f64::from(repr.ttf_parser.units_per_em());
+
+    let mut starts: Vec<usize> =
+      shaped.glyphs.iter().map(|glyph| glyph.cluster).collect();
+    starts.sort_unstable();
+    starts.dedup();
+    let clusters = starts
+      .iter()
+      .enumerate()
+      .map(|(id, &start)| {
+        let end = starts.get(id + 1).copied().unwrap_or(text.len());
+        let advance: i32 = shaped
+          .glyphs
+          .iter()
+          .filter(|glyph| glyph.cluster == start)
+          .map(|glyph| shaped.advance_of(glyph))
+          .sum();
+        ClusterAdvance {
+          text: text.get(start..end).unwrap_or_default().to_string(),
+          start,
+          end,
+          advance: f64::from(advance) * scale,
+        }
+      })
+      .collect();
+    let advance: i32 = shaped
+      .glyphs
+      .iter()
+      .map(|glyph| shaped.advance_of(glyph))
+      .sum();
+    let bbox = shaped.ink_bbox(repr).map(|bbox| BBox {
+      x_min: f64::from(bbox.x_min) * scale,
+      y_min: f64::from(bbox.y_min) * scale,
+      x_max: f64::from(bbox.x_max) * scale,
+      y_max: f64::from(bbox.y_max) * scale,
+    });
+    TextMeasure {
+      advance: f64::from(advance) * scale,
+      bbox,
+      clusters,
+    }
+  }
+}
@@ -0,0 +1,298 @@
+/// This module lays glyphs of a face out on a grid and renders them into
+/// one SVG specimen sheet.
+use super::repr::FontRepr;
+use super::ttf::agl;
+use super::ttf::glyphs::{SvgBuilder, escape};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use ttf_parser::GlyphId;
+use ttf_parser::cmap::Subtable;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum GridLabel {
+  Name,
+  Id,
+  Codepoint,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct GlyphGridOptions {
+  // inclusive range of glyph ids, all glyphs if neither range is given
+  pub(crate) glyphs: Option<[u16; 2]>,
+  // inclusive range of codepoints, unmapped ones are skipped, takes
+  // precedence over `glyphs`
+  pub(crate) codepoints: Option<[u32; 2]>,
+  // side length of square cells in SVG user units
+  pub(crate) cell: f32,
+  pub(crate) columns: u32,
+  // labels below each glyph, top to bottom
+  pub(crate) labels: Vec<GridLabel>,
+  pub(crate) baseline: bool,
+  // mark the origin and the advance of each glyph
+  pub(crate) advance: bool,
+  // glyphs per page, all glyphs on one page if not given
+  pub(crate) per_page: Option<u32>,
+  // zero-based page number
+  pub(crate) page: u32,
+  // SVG paints of glyphs, markers, cell borders and labels
+  pub(crate) fill: String,
+  pub(crate) guide: String,
+  pub(crate) border: String,
+  pub(crate) label: String,
+}
+
+impl Default for GlyphGridOptions {
+  fn default() -> Self {
+    GlyphGridOptions {
+      glyphs: None,
+      codepoints: None,
+      cell: 96.0,
+      columns: 16,
+      labels: vec![GridLabel::Name, GridLabel::Id, GridLabel::Codepoint],
+      baseline: true,
+      advance: true,
+      per_page: None,
+      page: 0,
+      fill: "black".to_string(),
+      guide: "#d33".to_string(),
+      border: "#ccc".to_string(),
+      label: "#666".to_string(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct GlyphGrid {
+  svg: String,
+  // size of the SVG document in user units
+  width: f32,
+  height: f32,
+  page: u32,
+  pages: u32,
+  // number of glyphs in the whole selection
+  total: usize,
+  // glyph ids on this page, in order
+  glyphs: Vec<u16>,
+}
+
+impl GlyphGrid {
+  /// Smallest codepoint mapped to each glyph.
+  fn reverse_cmap(repr: &FontRepr) -> Vec<Option<u32>> {
+    let ttf = &repr.ttf_parser;
+    let mut codepoints = vec![None; usize::from(ttf.number_of_glyphs())];
+    let Some(cmap) = ttf.tables().cmap else {
+      return codepoints;
+    };
+    for subtable in cmap.subtables.into_iter().filter(Subtable::is_unicode) {
+      subtable.codepoints(|code| {
+        if let Some(glyph) = subtable.glyph_index(code)
+          && let Some(slot) = codepoints.get_mut(usize::from(glyph.0))
+          && slot.is_none_or(|old| code < old)
+        {
+          *slot = Some(code);
+        }
+      });
+    }
+    codepoints
+  }
+
+  fn entries(
+    repr: &FontRepr,
+    options: &GlyphGridOptions,
+  ) -> Vec<(GlyphId, Option<u32>)> {
+    let ttf = &repr.ttf_parser;
+    if let Some([start, end]) = options.codepoints {
+      return (start..=end.min(u32::from(char::MAX)))
+        .filter_map(char::from_u32)
+        .filter_map(|ch| Some((ttf.glyph_index(ch)?, Some(u32::from(ch)))))
+        .collect();
+    }
+    let codepoints = Self::reverse_cmap(repr);
+    let last = ttf.number_of_glyphs().saturating_sub(1);
+    let [start, end] = options.glyphs.unwrap_or([0, last]);
+    (start..=end.min(last))
+      .map(|id| (GlyphId(id), codepoints[usize::from(id)]))
+      .collect()
+  }
+
+  #[allow(clippy::cast_precision_loss)]
+  pub(crate) fn from_repr(
+    repr: &FontRepr,
+    options: &GlyphGridOptions,
+  ) -> Option<Self> {
+    if !(options.cell.is_finite() && options.cell > 0.0)
+      || options.columns == 0
+    {
+      return None;
+    }
+    let entries = Self::entries(repr, options);
+    let total = entries.len();
+    let per_page = options
+      .per_page
+      .map_or(total, |count| count as usize)
+      .max(1);
+    let pages = total.div_ceil(per_page).max(1);
+    let page = options.page as usize;
+    if page >= pages {
+      return None;
+    }
+    let entries = &entries
+      [(page * per_page).min(total)..((page + 1) * per_page).min(total)];
+
+    let cell = options.cell;
+    let columns = options.columns as usize;
+    let rows = entries.len().div_ceil(columns).max(1);
+    let (width, height) = (columns as f32 * cell, rows as f32 * cell);
+    let layout = CellLayout::new(repr, options);
+
+    let mut svg = String::new();
+    write!(
+      &mut svg,
+      r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {width} {height}" width="{width}" height="{height}">
+<style>text {{ font-family: sans-serif; font-size: {size}px; fill: {label}; text-anchor: middle; }}</style>
+"#,
+      size = layout.label_size,
+      label = escape(&options.label),
+    )
+    .ok()?;
+    for (position, &(glyph_id, codepoint)) in entries.iter().enumerate() {
+      let x = (position % columns) as f32 * cell;
+      let y = (position / columns) as f32 * cell;
+      writeln!(&mut svg, r#"<g transform="translate({x} {y})">"#).ok()?;
+      layout
+        .write_cell(&mut svg, repr, options, glyph_id, codepoint)
+        .ok()?;
+      writeln!(&mut svg, "</g>").ok()?;
+    }
+    svg.push_str("</svg>");
+
+    Some(GlyphGrid {
+      svg,
+      width,
+      height,
+      page: options.page,
+      pages: u32::try_from(pages).ok()?,
+      total,
+      glyphs: entries.iter().map(|(glyph_id, _)| glyph_id.0).collect(),
+    })
+  }
+}
+
+/// Geometry shared by all cells, in SVG user units relative to the top
+/// left corner of a cell.
+struct CellLayout {
+  cell: f32,
+  padding: f32,
+  label_size: f32,
+  label_area: f32,
+  glyph_area: f32,
+  // SVG user units per font unit
+  scale: f32,
+  baseline: f32,
+}
+
+impl CellLayout {
+  #[allow(clippy::cast_precision_loss)]
+  fn new(repr: &FontRepr, options: &GlyphGridOptions) -> Self {
+    let ttf = &repr.ttf_parser;
+    let cell = options.cell;
+    // the em box from hhea fits into the area above the labels
+    let (ascender, descender) =
+      (f32::from(ttf.ascender()), f32::from(ttf.descender()));
+    let extent = if ascender > descender {
+      ascender - descender
+    } else {
+      f32::from(ttf.units_per_em())
+    };
+    let padding = cell * 0.06;
+    let label_size = cell * 0.09;
+    let label_area = options.labels.len() as f32 * label_size * 1.25;
+    let glyph_area = (cell - 2.0 * padding - label_area).max(0.0);
+    let scale = glyph_area / extent;
+    CellLayout {
+      cell,
+      padding,
+      label_size,
+      label_area,
+      glyph_area,
+      scale,
+      baseline: padding + ascender.max(0.0) * scale,
+    }
+  }
+
+  #[allow(clippy::cast_precision_loss)]
+  fn write_cell(
+    &self,
+    svg: &mut String,
+    repr: &FontRepr,
+    options: &GlyphGridOptions,
+    glyph_id: GlyphId,
+    codepoint: Option<u32>,
+  ) -> std::fmt::Result {
+    let ttf = &repr.ttf_parser;
+    let CellLayout {
+      cell,
+      padding,
+      label_size,
+      label_area,
+      glyph_area,
+      scale,
+      baseline,
+    } = *self;
+    let advance =
+      f32::from(ttf.glyph_hor_advance(glyph_id).unwrap_or_default()) * scale;
+    let origin = (cell - advance) / 2.0;
+    let (border, guide) = (escape(&options.border), escape(&options.guide));
+    writeln!(
+      svg,
+      r#"  <rect width="{cell}" height="{cell}" fill="none" stroke="{border}" stroke-width="0.5" />"#,
+    )?;
+    if options.baseline {
+      writeln!(
+        svg,
+        r#"  <line x1="0" y1="{baseline}" x2="{cell}" y2="{baseline}" stroke="{guide}" stroke-width="0.5" />"#,
+      )?;
+    }
+    if options.advance {
+      let bottom = padding + glyph_area;
+      for marker in [origin, origin + advance] {
+        writeln!(
+          svg,
+          r#"  <line x1="{marker}" y1="{padding}" x2="{marker}" y2="{bottom}" stroke="{guide}" stroke-width="0.5" />"#,
+        )?;
+      }
+    }
+    let mut builder = SvgBuilder::new();
+    if ttf.outline_glyph(glyph_id, &mut builder).is_some() {
+      writeln!(
+        svg,
+        r#"  <path transform="translate({origin} {baseline}) scale({scale} -{scale})" d="{}" fill="{}" />"#,
+        builder.0.trim_end(),
+        escape(&options.fill),
+      )?;
+    }
+    for (line, label) in options.labels.iter().enumerate() {
+      let text = match label {
+        GridLabel::Name => {
+          ttf.glyph_name(glyph_id).map(escape).or_else(|| {
+            let code = char::from_u32(codepoint?)?;
+            Some(escape(&agl::glyph_name(&[code])))
+          })
+        }
+        GridLabel::Id => Some(glyph_id.0.to_string()),
+        GridLabel::Codepoint => codepoint.map(|code| format!("U+{code:04X}")),
+      };
+      let y =
+        cell - padding - label_area + (line as f32 + 1.0) * label_size * 1.25;
+      writeln!(
+        svg,
+        r#"  <text x="{}" y="{y}">{}</text>"#,
+        cell / 2.0,
+        text.unwrap_or_default(),
+      )?;
+    }
+    Ok(())
+  }
+}
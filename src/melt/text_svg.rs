@@ -0,0 +1,144 @@
+/// This module renders a shaped string into one SVG document, glyph
+/// outlines are defined once and placed with `<use>`.
+use super::repr::FontRepr;
+use super::shaping::{ShapedText, ShapingOptions};
+use super::ttf::glyphs::{BBox, SvgBuilder, escape};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct TextSvgOptions {
+  pub(crate) shaping: ShapingOptions,
+  // SVG paint of glyphs
+  pub(crate) fill: String,
+}
+
+impl Default for TextSvgOptions {
+  fn default() -> Self {
+    TextSvgOptions {
+      shaping: ShapingOptions::default(),
+      fill: "black".to_string(),
+    }
+  }
+}
+
+/// A rendered string, all quantities are in pt. The origin of the string
+/// is at `(-x_origin, -y_origin)` of the SVG viewport, y axis points up
+/// for `bbox` and down in SVG.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct TextSvg {
+  svg: String,
+  x_origin: f64,
+  y_origin: f64,
+  width: f64,
+  height: f64,
+  // pen advance along the writing direction
+  advance: f64,
+  // distances from the baseline to the ascender and descender of `hhea`
+  // (Typst's `top-edge` and `bottom-edge`), both positive usually
+  ascent: f64,
+  descent: f64,
+  bbox: Option<BBox<f64>>,
+}
+
+impl TextSvg {
+  pub(crate) fn from_repr(
+    repr: &FontRepr,
+    text: &str,
+    options: &TextSvgOptions,
+  ) -> Option<Self> {
+    let ttf = &repr.ttf_parser;
+    let size = options.shaping.size;
+    if !(size.is_finite() && size > 0.0) {
+      return None;
+    }
+    let shaped = ShapedText::shape(repr, text, &options.shaping);
+    let scale = size / f64::from(ttf.units_per_em());
+    let advance = f64::from(
+      shaped
+        .glyphs
+        .iter()
+        .map(|glyph| shaped.advance_of(glyph))
+        .sum::<i32>(),
+    ) * scale;
+    let ascent = f64::from(ttf.ascender()) * scale;
+    let descent = -f64::from(ttf.descender()) * scale;
+    let bbox = shaped.ink_bbox(repr).map(|bbox| BBox {
+      x_min: f64::from(bbox.x_min) * scale,
+      y_min: f64::from(bbox.y_min) * scale,
+      x_max: f64::from(bbox.x_max) * scale,
+      y_max: f64::from(bbox.y_max) * scale,
+    });
+
+    // the line box (or column box of vertical text) united with the ink
+    let (mut x_min, mut x_max, mut y_min, mut y_max) = if shaped.vertical {
+      (-size / 2.0, size / 2.0, -advance, 0.0)
+    } else {
+      (0.0, advance, -descent, ascent)
+    };
+    if let Some(bbox) = &bbox {
+      x_min = x_min.min(bbox.x_min);
+      x_max = x_max.max(bbox.x_max);
+      y_min = y_min.min(bbox.y_min);
+      y_max = y_max.max(bbox.y_max);
+    }
+    let (width, height) = (x_max - x_min, y_max - y_min);
+
+    // glyphs without outline (e.g. space) are neither defined nor used
+    let mut defs = String::new();
+    let mut defined = BTreeSet::new();
+    for glyph in &shaped.glyphs {
+      let mut builder = SvgBuilder::new();
+      if !defined.contains(&glyph.id.0)
+        && ttf.outline_glyph(glyph.id, &mut builder).is_some()
+      {
+        writeln!(
+          &mut defs,
+          r#"    <path id="g{}" d="{}" />"#,
+          glyph.id.0,
+          builder.0.trim_end()
+        )
+        .ok()?;
+        defined.insert(glyph.id.0);
+      }
+    }
+    let mut uses = String::new();
+    for (glyph, (x, y)) in shaped.glyphs.iter().zip(shaped.positions()) {
+      if !defined.contains(&glyph.id.0) {
+        continue;
+      }
+      writeln!(
+        &mut uses,
+        r##"    <use xlink:href="#g{}" transform="translate({} {}) scale({scale} -{scale})" />"##,
+        glyph.id.0,
+        f64::from(x) * scale,
+        f64::from(-y) * scale,
+      )
+      .ok()?;
+    }
+    let svg = format!(
+      r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" viewBox="{x_min} {} {width} {height}" width="{width}pt" height="{height}pt">
+  <defs>
+{defs}  </defs>
+  <g fill="{}">
+{uses}  </g>
+</svg>"#,
+      -y_max,
+      escape(&options.fill),
+    );
+
+    Some(TextSvg {
+      svg,
+      x_origin: x_min,
+      y_origin: -y_max,
+      width,
+      height,
+      advance,
+      ascent,
+      descent,
+      bbox,
+    })
+  }
+}
@@ -1,5 +1,16 @@
+pub mod agl;
+pub mod anchors;
+pub mod baselines;
+pub mod cff;
 pub mod features;
+pub mod gdef;
+pub mod glyf;
 pub mod glyphs;
+pub mod hinting;
+pub mod instances;
+pub mod meta;
 pub mod metrics;
 pub mod names;
+pub mod outline;
 pub mod scripts;
+pub mod vertical;
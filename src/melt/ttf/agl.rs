@@ -0,0 +1,196 @@
+/// This mod maps glyph names to Unicode and back following the Adobe Glyph
+/// List Specification, e.g. `f_f_i` to `U+0066 U+0066 U+0069` and `U+0041`
+/// to `A`, so that fonts without glyph names (`post` format 3) can be
+/// treated like those with names.
+/// - see: <https://github.com/adobe-type-tools/agl-specification>
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::LazyLock;
+
+/// The Adobe Glyph List, names to be recognized, e.g. `Acute` or `afii10017`.
+static AGL: LazyLock<HashMap<&'static str, Vec<char>>> =
+  LazyLock::new(|| {
+    include_str!("agl/glyphlist.txt")
+      .lines()
+      .filter(|line| !line.starts_with('#'))
+      .filter_map(|line| {
+        let (name, codes) = line.split_once(';')?;
+        let codes = codes
+          .split(' ')
+          .map(|code| {
+            u32::from_str_radix(code, 16).ok().and_then(char::from_u32)
+          })
+          .collect::<Option<Vec<char>>>()?;
+        Some((name, codes))
+      })
+      .collect()
+  });
+
+/// The Adobe Glyph List For New Fonts, names to be given to new glyphs.
+static AGLFN: LazyLock<HashMap<char, &'static str>> = LazyLock::new(|| {
+  include_str!("agl/aglfn.txt")
+    .lines()
+    .filter(|line| !line.starts_with('#'))
+    .filter_map(|line| {
+      let mut fields = line.split(';');
+      let code = u32::from_str_radix(fields.next()?, 16).ok()?;
+      Some((char::from_u32(code)?, fields.next()?))
+    })
+    .collect()
+});
+
+/// The name of a glyph for a sequence of characters, i.e. names of the
+/// AGLFN, `uniXXXX` or `uXXXXX`, joined by underscores for ligatures.
+pub(crate) fn glyph_name(codes: &[char]) -> String {
+  let mut name = String::new();
+  for (index, &code) in codes.iter().enumerate() {
+    if index > 0 {
+      name.push('_');
+    }
+    let _ = match AGLFN.get(&code) {
+      Some(known) => write!(name, "{known}"),
+      None if u32::from(code) <= 0xFFFF => {
+        write!(name, "uni{:04X}", u32::from(code))
+      }
+      None => write!(name, "u{:X}", u32::from(code)),
+    };
+  }
+  name
+}
+
+fn is_upper_hex(text: &str) -> bool {
+  text
+    .bytes()
+    .all(|byte| matches!(byte, b'0'..=b'9' | b'A'..=b'F'))
+}
+
+/// A scalar value, i.e. not a surrogate.
+fn scalar(hex: &str) -> Option<char> {
+  char::from_u32(u32::from_str_radix(hex, 16).ok()?)
+}
+
+/// Characters of one component, empty if it maps to none.
+fn component_codes(component: &str) -> Vec<char> {
+  if let Some(codes) = AGL.get(component) {
+    return codes.clone();
+  }
+  if let Some(hex) = component.strip_prefix("uni")
+    && !hex.is_empty()
+    && hex.len() % 4 == 0
+    && is_upper_hex(hex)
+  {
+    return (0..hex.len())
+      .step_by(4)
+      .map(|start| scalar(&hex[start..start + 4]))
+      .collect::<Option<Vec<char>>>()
+      .unwrap_or_default();
+  }
+  if let Some(hex) = component.strip_prefix('u')
+    && (4..=6).contains(&hex.len())
+    && is_upper_hex(hex)
+  {
+    return scalar(hex).into_iter().collect();
+  }
+  Vec::new()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct GlyphNameComponent {
+  name: String,
+  codepoints: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ParsedGlyphName {
+  name: String,
+  // the part before the first period, e.g. `f_f_i` of `f_f_i.liga`
+  base: String,
+  // the part after the first period, e.g. `sc` of `a.sc`
+  suffix: Option<String>,
+  // parts of `base` separated by underscores
+  components: Vec<GlyphNameComponent>,
+  // characters of all components, components mapping to none are skipped
+  codepoints: Vec<u32>,
+}
+
+impl ParsedGlyphName {
+  pub(crate) fn parse(name: &str) -> Self {
+    let (base, suffix) = match name.split_once('.') {
+      Some((base, suffix)) => (base, Some(suffix.to_string())),
+      None => (name, None),
+    };
+    let components: Vec<GlyphNameComponent> = base
+      .split('_')
+      .filter(|component| !component.is_empty())
+      .map(|component| GlyphNameComponent {
+        name: component.to_string(),
+        codepoints: component_codes(component)
+          .into_iter()
+          .map(u32::from)
+          .collect(),
+      })
+      .collect();
+    let codepoints = components
+      .iter()
+      .flat_map(|component| component.codepoints.iter().copied())
+      .collect();
+    ParsedGlyphName {
+      name: name.to_string(),
+      base: base.to_string(),
+      suffix,
+      components,
+      codepoints,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn codepoints(name: &str) -> Vec<u32> {
+    ParsedGlyphName::parse(name).codepoints
+  }
+
+  #[test]
+  fn specification_examples() {
+    let parsed =
+      ParsedGlyphName::parse("Lcommaaccent_uni20AC0308_u1040C.alternate");
+    assert_eq!(parsed.base, "Lcommaaccent_uni20AC0308_u1040C");
+    assert_eq!(parsed.suffix.as_deref(), Some("alternate"));
+    assert_eq!(parsed.codepoints, [0x013B, 0x20AC, 0x0308, 0x1040C]);
+    assert_eq!(codepoints("Lcommaaccent"), [0x013B]);
+    assert_eq!(codepoints("uni20AC0308"), [0x20AC, 0x0308]);
+    assert_eq!(codepoints("u1040C"), [0x1040C]);
+    assert_eq!(codepoints("uniE000"), [0xE000]);
+  }
+
+  #[test]
+  fn invalid_components() {
+    // surrogates
+    assert!(codepoints("uniD800").is_empty());
+    assert!(codepoints("uniD801DC0C").is_empty());
+    assert!(codepoints("uD800").is_empty());
+    // lowercase digits
+    assert!(codepoints("uni20ac").is_empty());
+    assert!(codepoints("u1040c").is_empty());
+    // lengths other than four digits each, or up to six
+    assert!(codepoints("uni20AC03").is_empty());
+    assert!(codepoints("u1F").is_empty());
+    assert!(codepoints("u10FFFFF").is_empty());
+    // beyond U+10FFFF
+    assert!(codepoints("u110000").is_empty());
+    // components mapping to none are skipped
+    assert_eq!(codepoints("foo_uni0041.bar"), [0x41]);
+    assert!(codepoints(".notdef").is_empty());
+  }
+
+  #[test]
+  fn names_of_codes() {
+    assert_eq!(glyph_name(&['f', 'f', 'i']), "f_f_i");
+    assert_eq!(glyph_name(&['\u{20AC}']), "Euro");
+    assert_eq!(glyph_name(&['\u{0308}']), "uni0308");
+    assert_eq!(glyph_name(&['\u{1040C}']), "u1040C");
+  }
+}
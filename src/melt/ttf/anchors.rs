@@ -0,0 +1,192 @@
+/// This mod extracts attachment anchors of `GPOS` mark-to-base,
+/// mark-to-ligature, mark-to-mark and cursive lookups, which decide where
+/// shapers put diacritics and how cursive glyphs join.
+use crate::melt::repr::FontRepr;
+use serde::{Deserialize, Serialize};
+use std::string::ToString;
+use ttf_parser::GlyphId;
+use ttf_parser::gpos::{AnchorMatrix, PositioningSubtable};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum AttachmentType {
+  MarkToBase,
+  MarkToLigature,
+  MarkToMark,
+  Cursive,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum AnchorKind {
+  // anchor of a mark, attached to a base anchor of the same class
+  Mark,
+  // anchor of a base, of a ligature component, or of a mark that other
+  // marks attach to
+  Base,
+  // cursive anchors, the exit of a glyph is joined to the entry of the next
+  Entry,
+  Exit,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct GlyphAnchor {
+  // index into the lookup list of `GPOS`
+  lookup: u16,
+  // index of the subtable in the lookup, mark classes are numbered per
+  // subtable
+  subtable: u16,
+  lookup_type: AttachmentType,
+  pub(crate) kind: AnchorKind,
+  // mark class, `None` for cursive anchors
+  class: Option<u16>,
+  // ligature component, mark-to-ligature bases only
+  component: Option<u16>,
+  pub(crate) x: i16,
+  pub(crate) y: i16,
+}
+
+/// Anchors of a base matrix row, one per mark class defined there.
+fn base_anchors(
+  matrix: &AnchorMatrix,
+  row: u16,
+) -> impl Iterator<Item = (u16, i16, i16)> {
+  (0..matrix.cols).filter_map(move |class| {
+    let anchor = matrix.get(row, class)?;
+    Some((class, anchor.x, anchor.y))
+  })
+}
+
+impl GlyphAnchor {
+  /// Every anchor defined for a glyph, in lookup order. All subtables are
+  /// reported, since a shaper moves on to the next subtable of a lookup if
+  /// the mark is covered but the base is not.
+  pub(crate) fn from_glyph_id(
+    repr: &FontRepr,
+    glyph_id: GlyphId,
+  ) -> Vec<Self> {
+    let Some(gpos) = repr.ttf_parser.tables().gpos else {
+      return Vec::new();
+    };
+    let mut anchors = Vec::new();
+    for (lookup, lookup_table) in (0..).zip(gpos.lookups) {
+      let subtables =
+        lookup_table.subtables.into_iter::<PositioningSubtable>();
+      for (subtable, positioning) in (0..).zip(subtables) {
+        let mut push = |lookup_type, kind, class, component, x, y| {
+          anchors.push(GlyphAnchor {
+            lookup,
+            subtable,
+            lookup_type,
+            kind,
+            class,
+            component,
+            x,
+            y,
+          });
+        };
+        match positioning {
+          PositioningSubtable::MarkToBase(table) => {
+            let lookup_type = AttachmentType::MarkToBase;
+            if let Some(index) = table.mark_coverage.get(glyph_id)
+              && let Some((class, anchor)) = table.marks.get(index)
+            {
+              let kind = AnchorKind::Mark;
+              push(lookup_type, kind, Some(class), None, anchor.x, anchor.y);
+            }
+            if let Some(row) = table.base_coverage.get(glyph_id) {
+              for (class, x, y) in base_anchors(&table.anchors, row) {
+                push(lookup_type, AnchorKind::Base, Some(class), None, x, y);
+              }
+            }
+          }
+          PositioningSubtable::MarkToLigature(table) => {
+            let lookup_type = AttachmentType::MarkToLigature;
+            if let Some(index) = table.mark_coverage.get(glyph_id)
+              && let Some((class, anchor)) = table.marks.get(index)
+            {
+              let kind = AnchorKind::Mark;
+              push(lookup_type, kind, Some(class), None, anchor.x, anchor.y);
+            }
+            if let Some(index) = table.ligature_coverage.get(glyph_id)
+              && let Some(matrix) = table.ligature_array.get(index)
+            {
+              for component in 0..matrix.rows {
+                for (class, x, y) in base_anchors(&matrix, component) {
+                  let (class, component) = (Some(class), Some(component));
+                  push(lookup_type, AnchorKind::Base, class, component, x, y);
+                }
+              }
+            }
+          }
+          PositioningSubtable::MarkToMark(table) => {
+            let lookup_type = AttachmentType::MarkToMark;
+            if let Some(index) = table.mark1_coverage.get(glyph_id)
+              && let Some((class, anchor)) = table.marks.get(index)
+            {
+              let kind = AnchorKind::Mark;
+              push(lookup_type, kind, Some(class), None, anchor.x, anchor.y);
+            }
+            if let Some(row) = table.mark2_coverage.get(glyph_id) {
+              for (class, x, y) in base_anchors(&table.mark2_matrix, row) {
+                push(lookup_type, AnchorKind::Base, Some(class), None, x, y);
+              }
+            }
+          }
+          PositioningSubtable::Cursive(table) => {
+            let lookup_type = AttachmentType::Cursive;
+            if let Some(index) = table.coverage.get(glyph_id) {
+              if let Some(anchor) = table.sets.entry(index) {
+                let kind = AnchorKind::Entry;
+                push(lookup_type, kind, None, None, anchor.x, anchor.y);
+              }
+              if let Some(anchor) = table.sets.exit(index) {
+                let kind = AnchorKind::Exit;
+                push(lookup_type, kind, None, None, anchor.x, anchor.y);
+              }
+            }
+          }
+          _ => {}
+        }
+      }
+    }
+    anchors
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct GlyphAnchors {
+  id: u16,
+  name: Option<String>,
+  // mark anchors carry the mark class of the glyph in their subtable
+  anchors: Vec<GlyphAnchor>,
+}
+
+impl GlyphAnchors {
+  fn from_glyph_id(repr: &FontRepr, glyph_id: GlyphId) -> Self {
+    GlyphAnchors {
+      id: glyph_id.0,
+      name: repr
+        .ttf_parser
+        .glyph_name(glyph_id)
+        .map(ToString::to_string),
+      anchors: GlyphAnchor::from_glyph_id(repr, glyph_id),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct GlyphsAnchors(Vec<Option<GlyphAnchors>>);
+
+impl GlyphsAnchors {
+  pub(crate) fn from_option_iter(
+    repr: &FontRepr,
+    codes: impl Iterator<Item = Option<char>>,
+  ) -> Self {
+    let anchors = codes.map(|code| {
+      let glyph_id = repr.ttf_parser.glyph_index(code?)?;
+      Some(GlyphAnchors::from_glyph_id(repr, glyph_id))
+    });
+    GlyphsAnchors(anchors.collect())
+  }
+}
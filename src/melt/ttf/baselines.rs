@@ -0,0 +1,169 @@
+/// This mod reads the `BASE` table: baseline positions per script (e.g.
+/// `romn`, `ideo`, `hang`, `math`, `icfb`, `icft`, `idtp`) and extents per
+/// language system, used to align runs of different scripts on one line.
+use crate::melt::repr::FontRepr;
+use serde::{Deserialize, Serialize};
+use skrifa::raw::tables::base::{Axis, BaseCoord, BaseScript, MinMax};
+use skrifa::raw::types::BigEndian;
+use skrifa::raw::{ReadError, TableProvider};
+use std::collections::BTreeMap;
+
+fn coordinate(coord: Option<Result<BaseCoord, ReadError>>) -> Option<i16> {
+  Some(coord?.ok()?.coordinate())
+}
+
+/// Extents for one feature, overriding those of its language system.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FeatureExtents {
+  feature: String,
+  min: Option<i16>,
+  max: Option<i16>,
+}
+
+/// Lowest and highest coordinates of glyphs, i.e. the descent and ascent
+/// of a script or language system in font units along the axis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Extents {
+  min: Option<i16>,
+  max: Option<i16>,
+  features: Vec<FeatureExtents>,
+}
+
+impl Extents {
+  fn from_min_max(min_max: &MinMax) -> Self {
+    let data = min_max.offset_data();
+    Extents {
+      min: coordinate(min_max.min_coord()),
+      max: coordinate(min_max.max_coord()),
+      features: min_max
+        .feat_min_max_records()
+        .iter()
+        .map(|record| FeatureExtents {
+          feature: record.feature_table_tag().to_string(),
+          min: coordinate(record.min_coord(data)),
+          max: coordinate(record.max_coord(data)),
+        })
+        .collect(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LanguageExtents {
+  language: String,
+  extents: Extents,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ScriptBaselines {
+  script: String,
+  // baseline the glyphs of this script are designed on
+  default_baseline: Option<String>,
+  // positions of baselines in font units, keyed by baseline tag
+  baselines: BTreeMap<String, i16>,
+  // extents of the default language system
+  extents: Option<Extents>,
+  languages: Vec<LanguageExtents>,
+}
+
+impl ScriptBaselines {
+  fn from_base_script(
+    tag: String,
+    script: &BaseScript,
+    tags: &[String],
+  ) -> Self {
+    let mut default_baseline = None;
+    let mut baselines = BTreeMap::new();
+    if let Some(Ok(values)) = script.base_values() {
+      default_baseline = tags
+        .get(usize::from(values.default_baseline_index()))
+        .cloned();
+      for (tag, coord) in tags.iter().zip(values.base_coords().iter()) {
+        if let Ok(coord) = coord {
+          baselines.insert(tag.clone(), coord.coordinate());
+        }
+      }
+    }
+    let data = script.offset_data();
+    ScriptBaselines {
+      script: tag,
+      default_baseline,
+      baselines,
+      extents: script
+        .default_min_max()
+        .and_then(Result::ok)
+        .map(|min_max| Extents::from_min_max(&min_max)),
+      languages: script
+        .base_lang_sys_records()
+        .iter()
+        .filter_map(|record| {
+          Some(LanguageExtents {
+            language: record.base_lang_sys_tag().to_string(),
+            extents: Extents::from_min_max(&record.min_max(data).ok()?),
+          })
+        })
+        .collect(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AxisBaselines {
+  // baseline tags defined for this axis, in order
+  tags: Vec<String>,
+  scripts: Vec<ScriptBaselines>,
+}
+
+impl AxisBaselines {
+  fn from_axis(axis: &Axis) -> Option<Self> {
+    let tags: Vec<String> = axis
+      .base_tag_list()
+      .and_then(Result::ok)
+      .map(|list| {
+        list
+          .baseline_tags()
+          .iter()
+          .map(|tag| BigEndian::get(tag).to_string())
+          .collect()
+      })
+      .unwrap_or_default();
+    let list = axis.base_script_list().ok()?;
+    let data = list.offset_data();
+    let scripts = list
+      .base_script_records()
+      .iter()
+      .filter_map(|record| {
+        let script = record.base_script(data).ok()?;
+        let tag = record.base_script_tag().to_string();
+        Some(ScriptBaselines::from_base_script(tag, &script, &tags))
+      })
+      .collect();
+    Some(AxisBaselines { tags, scripts })
+  }
+}
+
+/// Coordinates are y positions for the horizontal axis (horizontal text)
+/// and x positions for the vertical axis (vertical text).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct FontBaselines {
+  horizontal: Option<AxisBaselines>,
+  vertical: Option<AxisBaselines>,
+}
+
+impl FontBaselines {
+  pub(crate) fn from_repr(repr: &FontRepr) -> Self {
+    let Ok(base) = repr.font_ref.base() else {
+      return FontBaselines::default();
+    };
+    FontBaselines {
+      horizontal: base
+        .horiz_axis()
+        .and_then(Result::ok)
+        .and_then(|axis| AxisBaselines::from_axis(&axis)),
+      vertical: base
+        .vert_axis()
+        .and_then(Result::ok)
+        .and_then(|axis| AxisBaselines::from_axis(&axis)),
+    }
+  }
+}
@@ -0,0 +1,446 @@
+/// This mod reads the DICTs of `CFF ` and `CFF2` tables, i.e. font names,
+/// the font matrix, hinting parameters of private DICTs and, for CID-keyed
+/// fonts, the character collection (e.g. `Adobe-Japan1-7`) glyphs map to.
+use crate::melt::repr::FontRepr;
+use serde::{Deserialize, Serialize};
+use skrifa::raw::tables::cff::Cff;
+use skrifa::raw::tables::postscript::dict::{self, Entry};
+use skrifa::raw::tables::postscript::{
+  BlendState, Charset, FdSelect, Index, StringId,
+};
+use skrifa::raw::tables::variations::ItemVariationStore;
+use skrifa::raw::types::Fixed;
+use skrifa::raw::{FontData, FontRead, TableProvider};
+use skrifa::{FontRef, GlyphId};
+use std::ops::Range;
+
+fn string(cff: Option<&Cff>, id: StringId) -> Option<String> {
+  cff?.string(id).map(|string| string.to_string())
+}
+
+fn blues(values: &dict::Blues) -> Vec<[f32; 2]> {
+  values
+    .values()
+    .iter()
+    .map(|(bottom, top)| [bottom.to_f32(), top.to_f32()])
+    .collect()
+}
+
+fn stem_snaps(values: &dict::StemSnaps) -> Vec<f32> {
+  values.values().iter().copied().map(Fixed::to_f32).collect()
+}
+
+/// A real number operand, `bytes` following its `30` prefix.
+fn real(bytes: &mut impl Iterator<Item = u8>) -> Option<f64> {
+  let mut text = String::new();
+  for byte in bytes {
+    for nibble in [byte >> 4, byte & 0xf] {
+      match nibble {
+        0..=9 => text.push(char::from(b'0' + nibble)),
+        0xa => text.push('.'),
+        0xb => text.push('E'),
+        0xc => text.push_str("E-"),
+        0xe => text.push('-'),
+        0xf => return text.parse().ok(),
+        _ => return None,
+      }
+    }
+  }
+  None
+}
+
+/// The last `BlueScale` of a private DICT as stored, e.g. `0.039625`.
+/// read-fonts rounds it to 16.16 fixed point, this reads the operand
+/// itself and is `None` if it is blended.
+#[allow(clippy::cast_possible_truncation)]
+fn blue_scale(data: &[u8]) -> Option<f32> {
+  let mut operand = None;
+  let mut blue_scale = None;
+  let mut bytes = data.iter().copied();
+  while let Some(b0) = bytes.next() {
+    operand = match b0 {
+      12 => {
+        if bytes.next()? == 9 {
+          blue_scale = operand;
+        }
+        None
+      }
+      0..=21 => None,
+      28 => Some(f64::from(i16::from_be_bytes([
+        bytes.next()?,
+        bytes.next()?,
+      ]))),
+      29 => Some(f64::from(i32::from_be_bytes([
+        bytes.next()?,
+        bytes.next()?,
+        bytes.next()?,
+        bytes.next()?,
+      ]))),
+      30 => Some(real(&mut bytes)?),
+      32..=246 => Some(f64::from(b0) - 139.0),
+      247..=250 => {
+        Some(f64::from(b0 - 247) * 256.0 + f64::from(bytes.next()?) + 108.0)
+      }
+      251..=254 => {
+        Some(-f64::from(b0 - 251) * 256.0 - f64::from(bytes.next()?) - 108.0)
+      }
+      _ => return None,
+    };
+  }
+  blue_scale.map(|value| value as f32)
+}
+
+/// Hinting parameters of a private DICT, in font units. Unset values are
+/// `None`, see the CFF specification for their defaults.
+#[allow(clippy::struct_field_names)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct PrivateDict {
+  // bottom and top of alignment zones, the first is the baseline overshoot
+  blue_values: Vec<[f32; 2]>,
+  other_blues: Vec<[f32; 2]>,
+  family_blues: Vec<[f32; 2]>,
+  family_other_blues: Vec<[f32; 2]>,
+  blue_scale: Option<f32>,
+  blue_shift: Option<f32>,
+  blue_fuzz: Option<f32>,
+  // dominant horizontal and vertical stem widths
+  std_hw: Option<f32>,
+  std_vw: Option<f32>,
+  stem_snap_h: Vec<f32>,
+  stem_snap_v: Vec<f32>,
+  force_bold: bool,
+  // 1 for CJK ideographs, which are hinted differently
+  language_group: Option<i32>,
+  expansion_factor: Option<f32>,
+  default_width_x: Option<f32>,
+  nominal_width_x: Option<f32>,
+}
+
+impl PrivateDict {
+  fn from_data(data: &[u8], blend: Option<BlendState>) -> Self {
+    let mut private = PrivateDict::default();
+    for entry in dict::entries(data, blend).filter_map(Result::ok) {
+      match entry {
+        Entry::BlueValues(values) => private.blue_values = blues(&values),
+        Entry::OtherBlues(values) => private.other_blues = blues(&values),
+        Entry::FamilyBlues(values) => private.family_blues = blues(&values),
+        Entry::FamilyOtherBlues(values) => {
+          private.family_other_blues = blues(&values);
+        }
+        Entry::BlueScale(value) => {
+          private.blue_scale = blue_scale(data).or(Some(value.to_f32()));
+        }
+        Entry::BlueShift(value) => private.blue_shift = Some(value.to_f32()),
+        Entry::BlueFuzz(value) => private.blue_fuzz = Some(value.to_f32()),
+        Entry::StdHw(value) => private.std_hw = Some(value.to_f32()),
+        Entry::StdVw(value) => private.std_vw = Some(value.to_f32()),
+        Entry::StemSnapH(values) => private.stem_snap_h = stem_snaps(&values),
+        Entry::StemSnapV(values) => private.stem_snap_v = stem_snaps(&values),
+        Entry::ForceBold(value) => private.force_bold = value,
+        Entry::LanguageGroup(value) => private.language_group = Some(value),
+        Entry::ExpansionFactor(value) => {
+          private.expansion_factor = Some(value.to_f32());
+        }
+        Entry::DefaultWidthX(value) => {
+          private.default_width_x = Some(value.to_f32());
+        }
+        Entry::NominalWidthX(value) => {
+          private.nominal_width_x = Some(value.to_f32());
+        }
+        _ => {}
+      }
+    }
+    private
+  }
+}
+
+/// A font DICT of the `FDArray`, CID-keyed `CFF ` fonts and `CFF2` fonts
+/// select one per glyph.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FontDict {
+  // e.g. `KozMinPr6N-Regular-Kana`, `CFF ` only
+  name: Option<String>,
+  private_dict: Option<PrivateDict>,
+}
+
+/// Registry, ordering and supplement, e.g. `Adobe`, `Japan1` and `7`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CidSystemInfo {
+  registry: Option<String>,
+  ordering: Option<String>,
+  supplement: i32,
+  // number of CIDs in the font, 8720 if unset
+  count: Option<u32>,
+  version: Option<f32>,
+}
+
+/// The Top DICT, `CFF2` only has the font matrix of these fields.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TopDict {
+  version: Option<String>,
+  notice: Option<String>,
+  copyright: Option<String>,
+  full_name: Option<String>,
+  family_name: Option<String>,
+  weight: Option<String>,
+  // `[xx, xy, yx, yy, dx, dy]` from glyph space to text space
+  font_matrix: [f32; 6],
+  font_bbox: Option<[f32; 4]>,
+  is_fixed_pitch: bool,
+  italic_angle: f32,
+  underline_position: f32,
+  underline_thickness: f32,
+  paint_type: i32,
+  charstring_type: i32,
+  unique_id: Option<i32>,
+}
+
+impl Default for TopDict {
+  fn default() -> Self {
+    TopDict {
+      version: None,
+      notice: None,
+      copyright: None,
+      full_name: None,
+      family_name: None,
+      weight: None,
+      font_matrix: [0.001, 0.0, 0.0, 0.001, 0.0, 0.0],
+      font_bbox: None,
+      is_fixed_pitch: false,
+      italic_angle: 0.0,
+      underline_position: -100.0,
+      underline_thickness: 50.0,
+      paint_type: 0,
+      charstring_type: 2,
+      unique_id: None,
+    }
+  }
+}
+
+/// Offsets and ranges of a Top DICT, resolved against the table data.
+#[derive(Default)]
+struct TopDictData<'a> {
+  top_dict: TopDict,
+  cid: Option<CidSystemInfo>,
+  private_dict_range: Option<Range<usize>>,
+  fd_array: Option<Index<'a>>,
+  fd_select: Option<FdSelect<'a>>,
+  var_store: Option<ItemVariationStore<'a>>,
+}
+
+impl<'a> TopDictData<'a> {
+  #[allow(clippy::cast_precision_loss)]
+  fn new(table: &'a [u8], data: &[u8], cff: Option<&Cff>) -> Self {
+    let is_cff2 = cff.is_none();
+    let mut items = TopDictData::default();
+    let top = &mut items.top_dict;
+    for entry in dict::entries(data, None).filter_map(Result::ok) {
+      match entry {
+        Entry::Version(id) => top.version = string(cff, id),
+        Entry::Notice(id) => top.notice = string(cff, id),
+        Entry::Copyright(id) => top.copyright = string(cff, id),
+        Entry::FullName(id) => top.full_name = string(cff, id),
+        Entry::FamilyName(id) => top.family_name = string(cff, id),
+        Entry::Weight(id) => top.weight = string(cff, id),
+        // values are scaled by `scale`, so that they keep their precision
+        Entry::FontMatrix(matrix, scale) => {
+          top.font_matrix = matrix.map(|value| value.to_f32() / scale as f32);
+        }
+        Entry::FontBbox(bbox) => {
+          top.font_bbox = Some(bbox.map(Fixed::to_f32));
+        }
+        Entry::IsFixedPitch(value) => top.is_fixed_pitch = value,
+        Entry::ItalicAngle(value) => top.italic_angle = value.to_f32(),
+        Entry::UnderlinePosition(value) => {
+          top.underline_position = value.to_f32();
+        }
+        Entry::UnderlineThickness(value) => {
+          top.underline_thickness = value.to_f32();
+        }
+        Entry::PaintType(value) => top.paint_type = value,
+        Entry::CharstringType(value) => top.charstring_type = value,
+        Entry::UniqueId(value) => top.unique_id = Some(value),
+        Entry::Ros {
+          registry,
+          ordering,
+          supplement,
+        } => {
+          let cid = items.cid.get_or_insert(CidSystemInfo {
+            registry: None,
+            ordering: None,
+            supplement: 0,
+            count: None,
+            version: None,
+          });
+          cid.registry = string(cff, registry);
+          cid.ordering = string(cff, ordering);
+          cid.supplement = supplement.to_i32();
+        }
+        Entry::CidCount(value) => {
+          if let Some(cid) = &mut items.cid {
+            cid.count = Some(value);
+          }
+        }
+        Entry::CidFontVersion(value) => {
+          if let Some(cid) = &mut items.cid {
+            cid.version = Some(value.to_f32());
+          }
+        }
+        Entry::PrivateDictRange(range) => {
+          items.private_dict_range = Some(range);
+        }
+        Entry::FdArrayOffset(offset) => {
+          items.fd_array = table
+            .get(offset..)
+            .and_then(|data| Index::new(data, is_cff2).ok());
+        }
+        Entry::FdSelectOffset(offset) => {
+          items.fd_select = table
+            .get(offset..)
+            .and_then(|data| FdSelect::read(FontData::new(data)).ok());
+        }
+        // the store is preceded by its length
+        Entry::VariationStoreOffset(offset) if is_cff2 => {
+          items.var_store =
+            table.get(offset.saturating_add(2)..).and_then(|data| {
+              ItemVariationStore::read(FontData::new(data)).ok()
+            });
+        }
+        _ => {}
+      }
+    }
+    items
+  }
+
+  /// Private DICTs of `CFF2` may blend values, they are read at the
+  /// default instance.
+  fn private_dict(&self, table: &[u8], range: Range<usize>) -> PrivateDict {
+    let blend = self
+      .var_store
+      .clone()
+      .and_then(|store| BlendState::new(store, &[], 0).ok());
+    let data = table.get(range).unwrap_or_default();
+    PrivateDict::from_data(data, blend)
+  }
+
+  fn font_dicts(&self, table: &[u8], cff: Option<&Cff>) -> Vec<FontDict> {
+    let Some(fd_array) = &self.fd_array else {
+      return Vec::new();
+    };
+    (0..fd_array.count() as usize)
+      .map(|index| {
+        let mut font_dict = FontDict {
+          name: None,
+          private_dict: None,
+        };
+        let data = fd_array.get(index).unwrap_or_default();
+        for entry in dict::entries(data, None).filter_map(Result::ok) {
+          match entry {
+            Entry::FontName(id) => font_dict.name = string(cff, id),
+            Entry::PrivateDictRange(range) => {
+              font_dict.private_dict = Some(self.private_dict(table, range));
+            }
+            _ => {}
+          }
+        }
+        font_dict
+      })
+      .collect()
+  }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct FontCff {
+  // 1 for `CFF `, 2 for `CFF2`, `None` if the font has no such table
+  version: Option<u8>,
+  // the PostScript name in the Name INDEX, `CFF ` only
+  font_name: Option<String>,
+  top_dict: Option<TopDict>,
+  // `None` for CID-keyed and `CFF2` fonts, their private DICTs are in the
+  // font DICTs
+  private_dict: Option<PrivateDict>,
+  cid: Option<CidSystemInfo>,
+  // the FDArray
+  font_dicts: Vec<FontDict>,
+  fd_select_format: Option<u8>,
+}
+
+impl FontCff {
+  pub(crate) fn from_repr(repr: &FontRepr) -> Self {
+    let font = &repr.font_ref;
+    if let Ok(cff) = font.cff() {
+      let table = cff.offset_data().as_bytes();
+      let Ok(data) = cff.top_dicts().get(0) else {
+        return FontCff::default();
+      };
+      let items = TopDictData::new(table, data, Some(&cff));
+      FontCff {
+        version: Some(1),
+        font_name: cff.name(0).map(|name| name.to_string()),
+        private_dict: items
+          .private_dict_range
+          .clone()
+          .filter(|_| items.fd_array.is_none())
+          .map(|range| items.private_dict(table, range)),
+        font_dicts: items.font_dicts(table, Some(&cff)),
+        fd_select_format: items.fd_select.as_ref().map(FdSelect::format),
+        cid: items.cid,
+        top_dict: Some(items.top_dict),
+      }
+    } else if let Ok(cff2) = font.cff2() {
+      let table = cff2.offset_data().as_bytes();
+      let items = TopDictData::new(table, cff2.top_dict_data(), None);
+      FontCff {
+        version: Some(2),
+        font_name: None,
+        private_dict: None,
+        font_dicts: items.font_dicts(table, None),
+        fd_select_format: items.fd_select.as_ref().map(FdSelect::format),
+        cid: None,
+        top_dict: Some(items.top_dict),
+      }
+    } else {
+      FontCff::default()
+    }
+  }
+}
+
+/// The charset of a CID-keyed `CFF ` font, which stores the CID of each
+/// glyph, e.g. its number in `Adobe-Japan1`, in place of a glyph name. It is
+/// read once per font, see [`FontRepr::cid_charset`].
+pub(crate) struct CidCharset<'a>(Charset<'a>);
+
+impl<'a> CidCharset<'a> {
+  /// `None` unless the font is a CID-keyed `CFF ` font.
+  pub(crate) fn new(font: &FontRef<'a>) -> Option<Self> {
+    let cff = font.cff().ok()?;
+    let table = cff.offset_data();
+    let (mut is_cid, mut charset, mut glyphs) = (false, None, None);
+    for entry in dict::entries(cff.top_dicts().get(0).ok()?, None) {
+      match entry {
+        Ok(Entry::Ros { .. }) => is_cid = true,
+        Ok(Entry::Charset(offset)) => charset = Some(offset),
+        Ok(Entry::CharstringsOffset(offset)) => {
+          glyphs = table
+            .as_bytes()
+            .get(offset..)
+            .and_then(|data| Index::new(data, false).ok())
+            .map(|charstrings| charstrings.count());
+        }
+        _ => {}
+      }
+    }
+    if !is_cid {
+      return None;
+    }
+    // `Cff::charset` only reads charsets of name-keyed fonts
+    Charset::new(table, charset?, glyphs?).ok().map(CidCharset)
+  }
+
+  /// The CID of a glyph.
+  pub(crate) fn cid(&self, glyph_id: ttf_parser::GlyphId) -> Option<u16> {
+    let cid = self.0.string_id(GlyphId::from(glyph_id.0)).ok()?;
+    Some(cid.to_u16())
+  }
+}
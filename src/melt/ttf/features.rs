@@ -1,4 +1,7 @@
+mod registry;
+
 use crate::melt::repr::FontRepr;
+pub(crate) use registry::FeatureTagInfo;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeSet;
 use ttf_parser::{self, opentype_layout};
@@ -27,4 +30,13 @@ impl FontFeatures {
 
     FontFeatures(gpos.into_iter().chain(gsub).collect())
   }
+
+  /// Registered names, categories and defaults of the features, in order.
+  pub(crate) fn tags(&self) -> Vec<FeatureTagInfo> {
+    self
+      .0
+      .iter()
+      .map(|tag| FeatureTagInfo::from_tag(tag))
+      .collect()
+  }
 }
@@ -0,0 +1,93 @@
+/// This mod describes OpenType feature tags, e.g. `liga` or `init`, with
+/// their friendly names, a category and whether shapers apply them by
+/// default. The tables in `registry/` are generated by
+/// `scripts/otfeatures.py`.
+/// - see: <https://learn.microsoft.com/en-us/typography/opentype/spec/featurelist>
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::LazyLock;
+
+struct FeatureRecord {
+  tag: &'static str,
+  name: &'static str,
+  category: &'static str,
+  default: &'static str,
+  shapers: Vec<&'static str>,
+}
+
+fn words(field: &'static str) -> Vec<&'static str> {
+  field.split(' ').filter(|word| !word.is_empty()).collect()
+}
+
+/// Records sorted by tag.
+static FEATURES: LazyLock<Vec<FeatureRecord>> = LazyLock::new(|| {
+  include_str!("registry/features.txt")
+    .lines()
+    .filter(|line| !line.starts_with('#'))
+    .filter_map(|line| {
+      let mut fields = line.splitn(5, ';');
+      Some(FeatureRecord {
+        tag: fields.next()?,
+        name: fields.next()?,
+        category: fields.next()?,
+        default: fields.next()?,
+        shapers: words(fields.next()?),
+      })
+    })
+    .collect()
+});
+
+/// ISO 15924 codes of the scripts of each shaper, e.g. `indic`.
+static SHAPERS: LazyLock<Vec<(&'static str, Vec<&'static str>)>> =
+  LazyLock::new(|| {
+    include_str!("registry/shapers.txt")
+      .lines()
+      .filter(|line| !line.starts_with('#'))
+      .filter_map(|line| {
+        let (shaper, codes) = line.split_once(';')?;
+        Some((shaper, words(codes)))
+      })
+      .collect()
+  });
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct FeatureTagInfo {
+  tag: String,
+  // e.g. `Standard Ligatures`, `None` if the tag is not registered
+  name: Option<String>,
+  // e.g. `ligatures`, `numerals` or `positional-forms`
+  category: Option<String>,
+  // when shapers apply it, `off` unless users enable it, see README
+  default: String,
+  // ISO 15924 codes of the scripts whose shapers apply it, e.g. `Arab` and
+  // `Deva` for `init`, empty for features not specific to scripts
+  scripts: BTreeSet<String>,
+}
+
+impl FeatureTagInfo {
+  pub(crate) fn from_tag(tag: &str) -> Self {
+    let Ok(index) = FEATURES.binary_search_by(|record| record.tag.cmp(tag))
+    else {
+      return FeatureTagInfo {
+        tag: tag.to_string(),
+        name: None,
+        category: None,
+        default: "off".to_string(),
+        scripts: BTreeSet::new(),
+      };
+    };
+    let record = &FEATURES[index];
+    let scripts = SHAPERS
+      .iter()
+      .filter(|(shaper, _)| record.shapers.contains(shaper))
+      .flat_map(|(_, codes)| codes.iter().copied().map(str::to_string))
+      .collect();
+    FeatureTagInfo {
+      tag: tag.to_string(),
+      name: Some(record.name.to_string()),
+      category: Some(record.category.to_string()),
+      default: record.default.to_string(),
+      scripts,
+    }
+  }
+}
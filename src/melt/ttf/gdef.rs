@@ -0,0 +1,315 @@
+/// This mod reads the `GDEF` table: glyph classes, mark attachment classes,
+/// mark glyph sets, attachment points and ligature carets, which tell
+/// shapers how to treat marks and where cursors stop inside ligatures.
+use crate::melt::repr::FontRepr;
+use serde::{Deserialize, Serialize};
+use skrifa::instance::Location;
+use skrifa::raw::TableProvider;
+use skrifa::raw::tables::gdef::{
+  CaretValue, ClassDef, DeviceOrVariationIndex, Gdef, GlyphClassDef,
+};
+use skrifa::raw::tables::variations::{DeltaSetIndex, ItemVariationStore};
+use skrifa::raw::types::BigEndian;
+use skrifa::{GlyphId16, MetadataProvider};
+use ttf_parser::GlyphId;
+use ttf_parser::gdef::GlyphClass;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum GdefClass {
+  // single character, spacing glyph
+  Base,
+  // multiple character, spacing glyph
+  Ligature,
+  // non-spacing combining glyph
+  Mark,
+  // part of a single character, spacing glyph
+  Component,
+}
+
+impl GdefClass {
+  pub(crate) fn from_glyph_id(
+    repr: &FontRepr,
+    glyph_id: GlyphId,
+  ) -> Option<Self> {
+    Some(
+      match repr.ttf_parser.tables().gdef?.glyph_class(glyph_id)? {
+        GlyphClass::Base => GdefClass::Base,
+        GlyphClass::Ligature => GdefClass::Ligature,
+        GlyphClass::Mark => GdefClass::Mark,
+        GlyphClass::Component => GdefClass::Component,
+      },
+    )
+  }
+}
+
+/// Glyph ids of each class of the glyph class definition.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct GlyphClasses {
+  base: Vec<u16>,
+  ligature: Vec<u16>,
+  mark: Vec<u16>,
+  component: Vec<u16>,
+}
+
+impl GlyphClasses {
+  fn from_class_def(class_def: &ClassDef) -> Self {
+    let mut classes = GlyphClasses::default();
+    for (glyph_id, class) in class_def.iter() {
+      let glyphs = match GlyphClassDef::new(class) {
+        GlyphClassDef::Base => &mut classes.base,
+        GlyphClassDef::Ligature => &mut classes.ligature,
+        GlyphClassDef::Mark => &mut classes.mark,
+        GlyphClassDef::Component => &mut classes.component,
+        GlyphClassDef::Unknown => continue,
+      };
+      glyphs.push(glyph_id.to_u16());
+    }
+    classes
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MarkAttachmentClass {
+  class: u16,
+  glyphs: Vec<u16>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AttachPoints {
+  glyph: u16,
+  // contour point indices
+  points: Vec<u16>,
+}
+
+/// Hinting adjustments of a caret, one delta in pixels for each ppem from
+/// `start_size` to `end_size`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CaretDevice {
+  start_size: u16,
+  end_size: u16,
+  deltas: Vec<i8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LigatureCaret {
+  // position along the writing direction in font units, formats 1 and 3
+  coordinate: Option<i16>,
+  // contour point of the glyph at the caret, format 2
+  point: Option<u16>,
+  device: Option<CaretDevice>,
+  // outer and inner index into the item variation store of `GDEF`
+  variation: Option<[u16; 2]>,
+  // adjustment of `coordinate` at each named instance, in `fvar` order
+  variation_deltas: Vec<i32>,
+}
+
+/// The item variation store of `GDEF` and the normalized coordinates of
+/// each named instance, to resolve format 3 carets with.
+struct CaretVariations<'a> {
+  store: Option<ItemVariationStore<'a>>,
+  instances: Vec<Location>,
+}
+
+impl CaretVariations<'_> {
+  fn deltas(&self, index: DeltaSetIndex) -> Vec<i32> {
+    let Some(store) = &self.store else {
+      return Vec::new();
+    };
+    self
+      .instances
+      .iter()
+      .map(|location| {
+        store
+          .compute_delta(index, location.coords())
+          .unwrap_or_default()
+      })
+      .collect()
+  }
+}
+
+impl LigatureCaret {
+  fn from_caret_value(
+    caret: &CaretValue,
+    variations: &CaretVariations,
+  ) -> Self {
+    let mut ligature_caret = LigatureCaret {
+      coordinate: None,
+      point: None,
+      device: None,
+      variation: None,
+      variation_deltas: Vec::new(),
+    };
+    match caret {
+      CaretValue::Format1(caret) => {
+        ligature_caret.coordinate = Some(caret.coordinate());
+      }
+      CaretValue::Format2(caret) => {
+        ligature_caret.point = Some(caret.caret_value_point_index());
+      }
+      CaretValue::Format3(caret) => {
+        ligature_caret.coordinate = Some(caret.coordinate());
+        match caret.device() {
+          Ok(DeviceOrVariationIndex::Device(device)) => {
+            ligature_caret.device = Some(CaretDevice {
+              start_size: device.start_size(),
+              end_size: device.end_size(),
+              deltas: device.iter().collect(),
+            });
+          }
+          Ok(DeviceOrVariationIndex::VariationIndex(index)) => {
+            let (outer, inner) =
+              (index.delta_set_outer_index(), index.delta_set_inner_index());
+            ligature_caret.variation = Some([outer, inner]);
+            ligature_caret.variation_deltas =
+              variations.deltas(DeltaSetIndex { outer, inner });
+          }
+          Err(_) => {}
+        }
+      }
+    }
+    ligature_caret
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LigatureCarets {
+  glyph: u16,
+  // one caret between each pair of components, in order
+  carets: Vec<LigatureCaret>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct FontGdef {
+  // e.g. `1.3`, `None` if the font has no `GDEF` table
+  version: Option<String>,
+  glyph_classes: Option<GlyphClasses>,
+  mark_attachment_classes: Vec<MarkAttachmentClass>,
+  // glyph ids of each set, in set order, lookups refer to them by index
+  mark_glyph_sets: Vec<Vec<u16>>,
+  attach_points: Vec<AttachPoints>,
+  ligature_carets: Vec<LigatureCarets>,
+  // whether format 3 carets may be adjusted by font variations
+  has_variation_store: bool,
+}
+
+impl FontGdef {
+  fn mark_attachment_classes(gdef: &Gdef) -> Vec<MarkAttachmentClass> {
+    let Some(Ok(class_def)) = gdef.mark_attach_class_def() else {
+      return Vec::new();
+    };
+    let mut classes: Vec<MarkAttachmentClass> = Vec::new();
+    let mut glyphs: Vec<(u16, u16)> = class_def
+      .iter()
+      .map(|(glyph_id, class)| (class, glyph_id.to_u16()))
+      .filter(|&(class, _)| class != 0)
+      .collect();
+    glyphs.sort_unstable();
+    for (class, glyph) in glyphs {
+      match classes.last_mut() {
+        Some(last) if last.class == class => last.glyphs.push(glyph),
+        _ => classes.push(MarkAttachmentClass {
+          class,
+          glyphs: vec![glyph],
+        }),
+      }
+    }
+    classes
+  }
+
+  fn mark_glyph_sets(gdef: &Gdef) -> Vec<Vec<u16>> {
+    let Some(Ok(sets)) = gdef.mark_glyph_sets_def() else {
+      return Vec::new();
+    };
+    sets
+      .coverages()
+      .iter()
+      .map(|coverage| {
+        coverage.map_or_else(
+          |_| Vec::new(),
+          |coverage| coverage.iter().map(GlyphId16::to_u16).collect(),
+        )
+      })
+      .collect()
+  }
+
+  fn attach_points(gdef: &Gdef) -> Vec<AttachPoints> {
+    let Some(Ok(list)) = gdef.attach_list() else {
+      return Vec::new();
+    };
+    let Ok(coverage) = list.coverage() else {
+      return Vec::new();
+    };
+    coverage
+      .iter()
+      .zip(list.attach_points().iter())
+      .filter_map(|(glyph, points)| {
+        Some(AttachPoints {
+          glyph: glyph.to_u16(),
+          points: points
+            .ok()?
+            .point_indices()
+            .iter()
+            .map(BigEndian::get)
+            .collect(),
+        })
+      })
+      .collect()
+  }
+
+  fn ligature_carets(repr: &FontRepr, gdef: &Gdef) -> Vec<LigatureCarets> {
+    let Some(Ok(list)) = gdef.lig_caret_list() else {
+      return Vec::new();
+    };
+    let Ok(coverage) = list.coverage() else {
+      return Vec::new();
+    };
+    let variations = CaretVariations {
+      store: gdef.item_var_store().and_then(Result::ok),
+      instances: repr
+        .font_ref
+        .named_instances()
+        .iter()
+        .map(|instance| instance.location())
+        .collect(),
+    };
+    coverage
+      .iter()
+      .zip(list.lig_glyphs().iter())
+      .filter_map(|(glyph, ligature)| {
+        Some(LigatureCarets {
+          glyph: glyph.to_u16(),
+          carets: ligature
+            .ok()?
+            .caret_values()
+            .iter()
+            .filter_map(|caret| {
+              Some(LigatureCaret::from_caret_value(&caret.ok()?, &variations))
+            })
+            .collect(),
+        })
+      })
+      .collect()
+  }
+
+  pub(crate) fn from_repr(repr: &FontRepr) -> Self {
+    let Ok(gdef) = repr.font_ref.gdef() else {
+      return FontGdef::default();
+    };
+    let version = gdef.version();
+    FontGdef {
+      version: Some(format!("{}.{}", version.major, version.minor)),
+      glyph_classes: gdef
+        .glyph_class_def()
+        .and_then(Result::ok)
+        .map(|class_def| GlyphClasses::from_class_def(&class_def)),
+      mark_attachment_classes: Self::mark_attachment_classes(&gdef),
+      mark_glyph_sets: Self::mark_glyph_sets(&gdef),
+      attach_points: Self::attach_points(&gdef),
+      ligature_carets: Self::ligature_carets(repr, &gdef),
+      has_variation_store: gdef
+        .item_var_store()
+        .is_some_and(|store| store.is_ok()),
+    }
+  }
+}
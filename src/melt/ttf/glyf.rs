@@ -0,0 +1,223 @@
+/// This mod exposes TrueType glyphs as they are stored in the `glyf` table,
+/// i.e. control points and component references instead of curves.
+use crate::melt::repr::FontRepr;
+use serde::{Deserialize, Serialize};
+use skrifa::raw::TableProvider;
+use skrifa::raw::tables::glyf::{
+  Anchor, CompositeGlyph, CompositeGlyphFlags, Glyf, Glyph, SimpleGlyph,
+};
+use skrifa::raw::tables::loca::Loca;
+use skrifa::raw::types::BigEndian;
+use std::string::ToString;
+
+use super::glyphs::BBox;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct GlyfPoint {
+  x: i16,
+  y: i16,
+  on_curve: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum ComponentScale {
+  None,
+  // one scale for both axes
+  Scale,
+  XyScale,
+  #[serde(rename = "2x2")]
+  TwoByTwo,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct GlyfComponent {
+  glyph: u16,
+  name: Option<String>,
+  // offset in font units, if the component is not placed by points
+  offset: Option<[i16; 2]>,
+  // point of the glyph built so far and point of the component to be
+  // matched, if the component is placed by points
+  anchor_points: Option<[u16; 2]>,
+  scale: ComponentScale,
+  // `[xx, yx, xy, yy]`
+  transform: [f32; 4],
+  use_my_metrics: bool,
+  round_xy_to_grid: bool,
+  // whether the offset is scaled by the transform, Apple style
+  scaled_offset: bool,
+  flags: u16,
+}
+
+impl GlyfComponent {
+  fn from_composite(repr: &FontRepr, glyph: &CompositeGlyph) -> Vec<Self> {
+    glyph
+      .components()
+      .map(|component| {
+        let flags = component.flags;
+        let (offset, anchor_points) = match component.anchor {
+          Anchor::Offset { x, y } => (Some([x, y]), None),
+          Anchor::Point { base, component } => {
+            (None, Some([base, component]))
+          }
+        };
+        let scale =
+          if flags.contains(CompositeGlyphFlags::WE_HAVE_A_TWO_BY_TWO) {
+            ComponentScale::TwoByTwo
+          } else if flags
+            .contains(CompositeGlyphFlags::WE_HAVE_AN_X_AND_Y_SCALE)
+          {
+            ComponentScale::XyScale
+          } else if flags.contains(CompositeGlyphFlags::WE_HAVE_A_SCALE) {
+            ComponentScale::Scale
+          } else {
+            ComponentScale::None
+          };
+        let transform = component.transform;
+        let id = component.glyph.to_u16();
+        GlyfComponent {
+          glyph: id,
+          name: repr
+            .ttf_parser
+            .glyph_name(ttf_parser::GlyphId(id))
+            .map(ToString::to_string),
+          offset,
+          anchor_points,
+          scale,
+          transform: [
+            transform.xx.to_f32(),
+            transform.yx.to_f32(),
+            transform.xy.to_f32(),
+            transform.yy.to_f32(),
+          ],
+          use_my_metrics: flags.contains(CompositeGlyphFlags::USE_MY_METRICS),
+          round_xy_to_grid: flags
+            .contains(CompositeGlyphFlags::ROUND_XY_TO_GRID),
+          scaled_offset: flags
+            .contains(CompositeGlyphFlags::SCALED_COMPONENT_OFFSET),
+          flags: flags.bits(),
+        }
+      })
+      .collect()
+  }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum GlyfKind {
+  // no data, e.g. space
+  Empty,
+  Simple,
+  Composite,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct GlyfGlyph {
+  id: u16,
+  kind: GlyfKind,
+  // bounding box stored in the glyph header
+  bbox: Option<BBox<i16>>,
+  instruction_length: usize,
+  // OVERLAP_SIMPLE or OVERLAP_COMPOUND
+  overlap: bool,
+  // simple glyphs only, contour `i` ends at point `end_points[i]`
+  points: Vec<GlyfPoint>,
+  end_points: Vec<u16>,
+  // composite glyphs only
+  components: Vec<GlyfComponent>,
+}
+
+impl GlyfGlyph {
+  fn empty(id: u16) -> Self {
+    GlyfGlyph {
+      id,
+      kind: GlyfKind::Empty,
+      bbox: None,
+      instruction_length: 0,
+      overlap: false,
+      points: Vec::new(),
+      end_points: Vec::new(),
+      components: Vec::new(),
+    }
+  }
+
+  fn from_simple(id: u16, glyph: &SimpleGlyph) -> Self {
+    GlyfGlyph {
+      kind: GlyfKind::Simple,
+      bbox: Some(BBox {
+        x_min: glyph.x_min(),
+        y_min: glyph.y_min(),
+        x_max: glyph.x_max(),
+        y_max: glyph.y_max(),
+      }),
+      instruction_length: glyph.instructions().len(),
+      overlap: glyph.has_overlapping_contours(),
+      points: glyph
+        .points()
+        .map(|point| GlyfPoint {
+          x: point.x,
+          y: point.y,
+          on_curve: point.on_curve,
+        })
+        .collect(),
+      end_points: glyph
+        .end_pts_of_contours()
+        .iter()
+        .map(BigEndian::get)
+        .collect(),
+      ..Self::empty(id)
+    }
+  }
+
+  fn from_composite(
+    repr: &FontRepr,
+    id: u16,
+    glyph: &CompositeGlyph,
+  ) -> Self {
+    let components = GlyfComponent::from_composite(repr, glyph);
+    GlyfGlyph {
+      kind: GlyfKind::Composite,
+      bbox: Some(BBox {
+        x_min: glyph.x_min(),
+        y_min: glyph.y_min(),
+        x_max: glyph.x_max(),
+        y_max: glyph.y_max(),
+      }),
+      instruction_length: glyph.instructions().map_or(0, <[u8]>::len),
+      // the flag must be set on the first component
+      overlap: glyph.component_glyphs_and_flags().next().is_some_and(
+        |(_, flags)| flags.contains(CompositeGlyphFlags::OVERLAP_COMPOUND),
+      ),
+      components,
+      ..Self::empty(id)
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct GlyphsGlyf(Vec<Option<GlyfGlyph>>);
+
+impl GlyphsGlyf {
+  /// `None` if the font has no `glyf` table, e.g. CFF fonts.
+  pub(crate) fn from_option_iter(
+    repr: &FontRepr,
+    codes: impl Iterator<Item = Option<char>>,
+  ) -> Option<Self> {
+    let font = &repr.font_ref;
+    let loca: Loca = font.loca(None).ok()?;
+    let glyf: Glyf = font.glyf().ok()?;
+    let glyphs = codes.map(|code| {
+      let id = repr.ttf_parser.glyph_index(code?)?.0;
+      match loca.get_glyf(id.into(), &glyf).ok()? {
+        None => Some(GlyfGlyph::empty(id)),
+        Some(Glyph::Simple(glyph)) => {
+          Some(GlyfGlyph::from_simple(id, &glyph))
+        }
+        Some(Glyph::Composite(glyph)) => {
+          Some(GlyfGlyph::from_composite(repr, id, &glyph))
+        }
+      }
+    });
+    Some(GlyphsGlyf(glyphs.collect()))
+  }
+}
@@ -4,6 +4,13 @@ use std::fmt::Write;
 use std::string::ToString;
 use ttf_parser::{GlyphId, OutlineBuilder};
 
+use super::agl;
+use super::anchors::{AnchorKind, GlyphAnchor};
+use super::gdef::GdefClass;
+use super::hinting::{Hinter, HintingOptions};
+use super::outline::{Outline, Synthesis};
+use super::vertical::{VerticalMetrics, vertical_glyph};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct Point<T> {
   x: T,
@@ -42,7 +49,7 @@ impl From<ttf_parser::PhantomPoints> for PhantomPoints<f32> {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct BBox<T> {
+pub(crate) struct BBox<T> {
   pub x_min: T,
   pub y_min: T,
   pub x_max: T,
@@ -60,6 +67,17 @@ impl From<ttf_parser::Rect> for BBox<i16> {
   }
 }
 
+impl From<ttf_parser::Rect> for BBox<f32> {
+  fn from(rect: ttf_parser::Rect) -> Self {
+    Self {
+      x_min: f32::from(rect.x_min),
+      y_min: f32::from(rect.y_min),
+      x_max: f32::from(rect.x_max),
+      y_max: f32::from(rect.y_max),
+    }
+  }
+}
+
 impl From<ttf_parser::RectF> for BBox<f32> {
   fn from(rect: ttf_parser::RectF) -> Self {
     Self {
@@ -82,6 +100,12 @@ pub(crate) struct GlyphInfo {
   pub(crate) horizontal_advance: Option<u16>,
   pub(crate) vertical_side_bearing: Option<i16>,
   pub(crate) horizontal_side_bearing: Option<i16>,
+  // class in the glyph class definition of `GDEF`
+  gdef_class: Option<GdefClass>,
+  // CID of CID-keyed `CFF ` fonts
+  cid: Option<u16>,
+  // whether `name` is derived from the character, the font has no name
+  synthesized_name: bool,
 
   is_color: bool,
 }
@@ -97,11 +121,13 @@ impl GlyphInfo {
     let bbox = ttf.glyph_bounding_box(glyph_id).map(BBox::from);
     let y_origin = ttf.glyph_y_origin(glyph_id);
     let vertical_advance = ttf.glyph_ver_advance(glyph_id);
-    let horizontal_advance = ttf.glyph_ver_advance(glyph_id);
+    let horizontal_advance = ttf.glyph_hor_advance(glyph_id);
     let vertical_side_bearing = ttf.glyph_ver_side_bearing(glyph_id);
     let horizontal_side_bearing = ttf.glyph_hor_side_bearing(glyph_id);
     let phantom_points =
       ttf.glyph_phantom_points(glyph_id).map(PhantomPoints::from);
+    let gdef_class = GdefClass::from_glyph_id(repr, glyph_id);
+    let cid = repr.cid_charset().and_then(|charset| charset.cid(glyph_id));
 
     let is_color = ttf.is_color_glyph(glyph_id);
     Self {
@@ -114,6 +140,9 @@ impl GlyphInfo {
       vertical_side_bearing,
       horizontal_side_bearing,
       phantom_points,
+      gdef_class,
+      cid,
+      synthesized_name: false,
 
       is_color,
     }
@@ -122,7 +151,12 @@ impl GlyphInfo {
   pub(crate) fn from_character(repr: &FontRepr, ch: char) -> Option<Self> {
     let ttf = &repr.ttf_parser;
     let glyph_id = ttf.glyph_index(ch)?;
-    Some(Self::from_glyph_id(repr, glyph_id))
+    let mut glyph = Self::from_glyph_id(repr, glyph_id);
+    if glyph.name.is_none() {
+      glyph.name = Some(agl::glyph_name(&[ch]));
+      glyph.synthesized_name = true;
+    }
+    Some(glyph)
   }
 
   #[allow(dead_code)]
@@ -150,14 +184,30 @@ impl GlyphsInfo {
   }
 }
 
-struct SvgBuilder(String);
+pub(crate) struct SvgBuilder(pub(crate) String);
 
 impl SvgBuilder {
-  fn new() -> Self {
+  pub(crate) fn new() -> Self {
     Self(String::new())
   }
 }
 
+/// Escapes text for XML content and attribute values.
+pub(crate) fn escape(text: &str) -> String {
+  text
+    .chars()
+    .fold(String::with_capacity(text.len()), |mut out, ch| {
+      match ch {
+        '&' => out.push_str("&amp;"),
+        '<' => out.push_str("&lt;"),
+        '>' => out.push_str("&gt;"),
+        '"' => out.push_str("&quot;"),
+        _ => out.push(ch),
+      }
+      out
+    })
+}
+
 // Implement the trait to translate Font commands -> SVG commands
 impl OutlineBuilder for SvgBuilder {
   fn move_to(&mut self, x: f32, y: f32) {
@@ -186,8 +236,12 @@ impl OutlineBuilder for SvgBuilder {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct SvgMetrics {
   x_origin: f32,
+  // top of the viewBox, the top of the ink in horizontal mode and the
+  // vertical origin in vertical mode
   y_origin: f32,
+  // horizontal advance
   width: f32,
+  // height of the ink in horizontal mode, vertical advance in vertical mode
   height: f32,
 }
 
@@ -198,25 +252,94 @@ pub(crate) struct GlyphShape {
   metrics: SvgMetrics,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct ShapeOptions {
+  pub(crate) synthesis: Synthesis,
+  // hint the outline at a pixel size, it is scaled back to font units
+  // afterwards so that templates stay comparable with unhinted ones
+  pub(crate) hinting: Option<HintingOptions>,
+  // set the glyph top to bottom: `vrt2`/`vert` substitutions are applied
+  // and the viewBox spans the vertical advance below the vertical origin
+  pub(crate) vertical: bool,
+  // draw the `GPOS` attachment anchors of the glyph as rings, marks in
+  // red, bases in blue and cursive entries and exits in green
+  pub(crate) anchors: bool,
+}
+
 impl GlyphShape {
-  fn shape(repr: &FontRepr, glyph: &GlyphInfo) -> Option<GlyphShape> {
+  fn shape(
+    repr: &FontRepr,
+    glyph: &GlyphInfo,
+    options: &ShapeOptions,
+    hinter: Option<&Hinter>,
+  ) -> Option<GlyphShape> {
     let mut builder = SvgBuilder::new();
     let ttf = &repr.ttf_parser;
     let glyph_id = glyph.id();
-    let bbox = ttf.outline_glyph(glyph_id, &mut builder)?;
-    let width = glyph.vertical_advance.map_or(
-      f32::from(bbox.width())
-        + 2.0 * f32::from(glyph.horizontal_side_bearing.unwrap_or(0)),
-      f32::from,
-    );
-    let height = f32::from(bbox.height());
-    let y_origin = f32::from(-bbox.y_max);
-    let x_origin = f32::from(bbox.x_min);
+    let synthesis = &options.synthesis;
+    let rect = ttf.outline_glyph(glyph_id, &mut builder)?;
+    let (bbox, advance) = if synthesis.is_identity() && hinter.is_none() {
+      (BBox::<f32>::from(rect), None)
+    } else {
+      let (mut outline, advance) = match hinter {
+        Some(hinter) => {
+          let glyph = hinter.hint(glyph_id)?;
+          let scale = f32::from(ttf.units_per_em()) / hinter.ppem;
+          let mut outline = glyph.outline;
+          outline.scale(scale);
+          (outline, glyph.advance.map(|advance| advance * scale))
+        }
+        None => (Outline::from_glyph_id(repr, glyph_id)?, None),
+      };
+      synthesis.apply(&mut outline);
+      builder = SvgBuilder::new();
+      outline.replay(&mut builder);
+      (outline.bbox()?, advance)
+    };
+    let width = synthesis.advance(advance.unwrap_or_else(|| {
+      glyph.horizontal_advance.map_or(
+        f32::from(rect.width())
+          + 2.0 * f32::from(glyph.horizontal_side_bearing.unwrap_or(0)),
+        f32::from,
+      )
+    }));
+    // anchors outside the ink are kept inside the viewBox
+    let mut overlay = String::new();
+    let (mut y_min, mut y_max) = (bbox.y_min, bbox.y_max);
+    if options.anchors {
+      let radius = f32::from(ttf.units_per_em()) / 40.0;
+      for anchor in GlyphAnchor::from_glyph_id(repr, glyph_id) {
+        let [x, y] =
+          synthesis.point([f32::from(anchor.x), f32::from(anchor.y)]);
+        let color = match anchor.kind {
+          AnchorKind::Mark => "#d33",
+          AnchorKind::Base => "#36c",
+          AnchorKind::Entry | AnchorKind::Exit => "#393",
+        };
+        write!(
+          &mut overlay,
+          r#"
+    <circle cx="{x}" cy="{y}" r="{radius}" fill="none" stroke="{color}" stroke-width="{}" />"#,
+          radius / 3.0,
+        )
+        .ok()?;
+        y_min = y_min.min(y - radius);
+        y_max = y_max.max(y + radius);
+      }
+    }
+    let (y_origin, height) = if options.vertical {
+      let vertical = VerticalMetrics::from_glyph_id(repr, glyph_id);
+      (-vertical.y_origin, vertical.advance)
+    } else {
+      (-y_max, y_max - y_min)
+    };
+    let x_origin = bbox.x_min;
     #[rustfmt::skip]
     let svg = format!(
 r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 {{y_origin}} {{width}} {{height}}">
   <g transform="scale({{scale}}, -{{scale}})">
-    <path d="{path_data}" fill="{{fill}}" fill-opacity="{{fill-opacity}}" stroke="{{stroke}}" stroke-width="{{stroke-width}}" {{extra}} />
+    <path d="{path_data}" fill="{{fill}}" fill-opacity="{{fill-opacity}}" stroke="{{stroke}}" stroke-width="{{stroke-width}}" {{extra}} />{overlay}
   </g>
 </svg>"#,
       path_data = builder.0
@@ -237,9 +360,15 @@ impl GlyphShape {
   pub(crate) fn from_character_styled(
     repr: &FontRepr,
     ch: char,
+    options: &ShapeOptions,
+    hinter: Option<&Hinter>,
   ) -> Option<Self> {
-    let glyph = GlyphInfo::from_character(repr, ch)?;
-    GlyphShape::shape(repr, &glyph)
+    let mut glyph = GlyphInfo::from_character(repr, ch)?;
+    if options.vertical {
+      glyph =
+        GlyphInfo::from_glyph_id(repr, vertical_glyph(repr, ch, glyph.id()));
+    }
+    GlyphShape::shape(repr, &glyph, options, hinter)
   }
 }
 
@@ -250,9 +379,15 @@ impl GlyphsShapes {
   pub(crate) fn from_option_iter(
     repr: &FontRepr,
     codes: impl Iterator<Item = Option<char>>,
-  ) -> Self {
-    let glyph_shapes =
-      codes.map(|code| GlyphShape::from_character_styled(repr, code?));
-    GlyphsShapes(glyph_shapes.collect())
+    options: &ShapeOptions,
+  ) -> Option<Self> {
+    let hinter = match &options.hinting {
+      Some(hinting) => Some(Hinter::new(repr, hinting)?),
+      None => None,
+    };
+    let glyph_shapes = codes.map(|code| {
+      GlyphShape::from_character_styled(repr, code?, options, hinter.as_ref())
+    });
+    Some(GlyphsShapes(glyph_shapes.collect()))
   }
 }
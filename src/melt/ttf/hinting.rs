@@ -0,0 +1,247 @@
+/// This mod hints glyph outlines at a pixel size with skrifa, either by
+/// running the TrueType bytecode of the font or with the autohinter.
+use crate::melt::repr::FontRepr;
+use serde::{Deserialize, Serialize};
+use skrifa::instance::{LocationRef, Size};
+use skrifa::metrics::GlyphMetrics;
+use skrifa::outline::{
+  DrawSettings, Engine, HintingInstance, OutlinePen, SmoothMode, Target,
+};
+use skrifa::{MetadataProvider, OutlineGlyphCollection};
+use ttf_parser::{GlyphId, OutlineBuilder};
+
+use super::glyphs::{BBox, GlyphInfo};
+use super::outline::Outline;
+
+impl OutlinePen for Outline {
+  fn move_to(&mut self, x: f32, y: f32) {
+    OutlineBuilder::move_to(self, x, y);
+  }
+
+  fn line_to(&mut self, x: f32, y: f32) {
+    OutlineBuilder::line_to(self, x, y);
+  }
+
+  fn quad_to(&mut self, cx0: f32, cy0: f32, x: f32, y: f32) {
+    OutlineBuilder::quad_to(self, cx0, cy0, x, y);
+  }
+
+  fn curve_to(
+    &mut self,
+    cx0: f32,
+    cy0: f32,
+    cx1: f32,
+    cy1: f32,
+    x: f32,
+    y: f32,
+  ) {
+    OutlineBuilder::curve_to(self, cx0, cy0, cx1, cy1, x, y);
+  }
+
+  fn close(&mut self) {
+    OutlineBuilder::close(self);
+  }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum HintingEngine {
+  // bytecode if the font is well hinted, the autohinter otherwise, like
+  // FreeType's default
+  #[default]
+  Auto,
+  // TrueType bytecode interpreter
+  Interpreter,
+  Autohinter,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum HintingTarget {
+  // strong hinting for 1-bit rendering
+  Mono,
+  // hinting for anti-aliased rendering
+  #[default]
+  Normal,
+  // only snaps vertically, keeps glyph widths
+  Light,
+  // for horizontal subpixel rendering
+  Lcd,
+  // for vertical subpixel rendering
+  VerticalLcd,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct HintingOptions {
+  // pixels per em
+  pub(crate) ppem: f32,
+  pub(crate) engine: HintingEngine,
+  pub(crate) target: HintingTarget,
+  // keep the unhinted advances instead of rounding them to pixels
+  pub(crate) preserve_linear_metrics: bool,
+}
+
+impl Default for HintingOptions {
+  fn default() -> Self {
+    HintingOptions {
+      ppem: 16.0,
+      engine: HintingEngine::default(),
+      target: HintingTarget::default(),
+      preserve_linear_metrics: false,
+    }
+  }
+}
+
+impl HintingOptions {
+  fn skrifa_options(&self) -> skrifa::outline::HintingOptions {
+    let engine = match self.engine {
+      HintingEngine::Auto => Engine::AutoFallback,
+      HintingEngine::Interpreter => Engine::Interpreter,
+      HintingEngine::Autohinter => Engine::Auto(None),
+    };
+    let mode = match self.target {
+      HintingTarget::Mono => None,
+      HintingTarget::Normal => Some(SmoothMode::Normal),
+      HintingTarget::Light => Some(SmoothMode::Light),
+      HintingTarget::Lcd => Some(SmoothMode::Lcd),
+      HintingTarget::VerticalLcd => Some(SmoothMode::VerticalLcd),
+    };
+    let target = mode.map_or(Target::Mono, |mode| Target::Smooth {
+      mode,
+      symmetric_rendering: true,
+      preserve_linear_metrics: self.preserve_linear_metrics,
+    });
+    skrifa::outline::HintingOptions { engine, target }
+  }
+}
+
+/// A hinting instance of a font at one size, set up once and shared by
+/// all glyphs since running the font program is costly.
+pub(crate) struct Hinter<'a> {
+  outlines: OutlineGlyphCollection<'a>,
+  instance: HintingInstance,
+  metrics: GlyphMetrics<'a>,
+  pub(crate) ppem: f32,
+}
+
+/// A hinted glyph, in pixels with y axis pointing up.
+pub(crate) struct HintedGlyph {
+  pub(crate) outline: Outline,
+  // advance adjusted by the hinter, the linear one if it did not touch it
+  pub(crate) advance: Option<f32>,
+  pub(crate) linear_advance: Option<f32>,
+  pub(crate) lsb: Option<f32>,
+}
+
+impl<'a> Hinter<'a> {
+  pub(crate) fn new(
+    repr: &FontRepr<'a>,
+    options: &HintingOptions,
+  ) -> Option<Self> {
+    if !(options.ppem.is_finite() && options.ppem > 0.0) {
+      return None;
+    }
+    let size = Size::new(options.ppem);
+    let outlines = repr.font_ref.outline_glyphs();
+    let instance = HintingInstance::new(
+      &outlines,
+      size,
+      LocationRef::default(),
+      options.skrifa_options(),
+    )
+    .ok()?;
+    let metrics = repr.font_ref.glyph_metrics(size, LocationRef::default());
+    Some(Hinter {
+      outlines,
+      instance,
+      metrics,
+      ppem: options.ppem,
+    })
+  }
+
+  pub(crate) fn hint(&self, glyph_id: GlyphId) -> Option<HintedGlyph> {
+    let glyph_id = skrifa::GlyphId::from(glyph_id.0);
+    let glyph = self.outlines.get(glyph_id)?;
+    let mut outline = Outline::default();
+    let adjusted = glyph
+      .draw(DrawSettings::hinted(&self.instance, false), &mut outline)
+      .ok()?;
+    let linear_advance = self.metrics.advance_width(glyph_id);
+    Some(HintedGlyph {
+      outline,
+      advance: adjusted.advance_width.or(linear_advance),
+      linear_advance,
+      lsb: adjusted.lsb,
+    })
+  }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct HintedOutlineOptions {
+  // convert quadratic Béziers to cubic ones
+  pub(crate) cubic: bool,
+  pub(crate) hinting: HintingOptions,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct HintedOutline {
+  id: u16,
+  ppem: f32,
+  // advances in pixels, `advance` is the hinted one
+  advance: Option<f32>,
+  linear_advance: Option<f32>,
+  lsb: Option<f32>,
+  bbox: Option<BBox<f32>>,
+  contours: Outline,
+}
+
+impl HintedOutline {
+  fn from_glyph(
+    hinter: &Hinter,
+    glyph: &GlyphInfo,
+    options: &HintedOutlineOptions,
+  ) -> Option<Self> {
+    let glyph_id = glyph.id();
+    let HintedGlyph {
+      outline,
+      advance,
+      linear_advance,
+      lsb,
+    } = hinter.hint(glyph_id)?;
+    let bbox = outline.bbox();
+    let contours = if options.cubic {
+      outline.into_cubic()
+    } else {
+      outline
+    };
+    Some(HintedOutline {
+      id: glyph_id.0,
+      ppem: hinter.ppem,
+      advance,
+      linear_advance,
+      lsb,
+      bbox,
+      contours,
+    })
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct HintedOutlines(Vec<Option<HintedOutline>>);
+
+impl HintedOutlines {
+  pub(crate) fn from_option_iter(
+    repr: &FontRepr,
+    codes: impl Iterator<Item = Option<char>>,
+    options: &HintedOutlineOptions,
+  ) -> Option<Self> {
+    let hinter = Hinter::new(repr, &options.hinting)?;
+    let outlines = codes.map(|code| {
+      let glyph = GlyphInfo::from_character(repr, code?)?;
+      HintedOutline::from_glyph(&hinter, &glyph, options)
+    });
+    Some(HintedOutlines(outlines.collect()))
+  }
+}
@@ -0,0 +1,163 @@
+/// This mod lists the named instances of variable fonts from `fvar`, e.g.
+/// `Light` or `SemiBold Condensed`, the way font menus of operating systems
+/// list them.
+use crate::melt::repr::FontRepr;
+use crate::melt::ttf::names::FontNames;
+use serde::{Deserialize, Serialize};
+use skrifa::raw::TableProvider;
+use skrifa::raw::tables::fvar::VariationAxisRecord;
+use skrifa::raw::types::{Fixed, NameId};
+use std::collections::BTreeMap;
+use ttf_parser::{Face, Style, name_id};
+use typst_library::layout::Ratio;
+use typst_library::text::{FontStretch, FontStyle, FontVariant, FontWeight};
+
+/// Positions of axes missing from `fvar`, from format 1, 2 and 3 axis
+/// values of `STAT`, e.g. `ital` of the italic file of a family split into
+/// an upright and an italic variable font. Axes with several values, e.g.
+/// `Roman` and `Italic`, are left out, as they do not tell which is this
+/// font's.
+fn stat_coordinates(repr: &FontRepr) -> BTreeMap<String, f32> {
+  let Ok(stat) = repr.font_ref.stat() else {
+    return BTreeMap::new();
+  };
+  let (Ok(design_axes), Some(Ok(values))) =
+    (stat.design_axes(), stat.offset_to_axis_values())
+  else {
+    return BTreeMap::new();
+  };
+  let variable: Vec<_> = repr
+    .font_ref
+    .fvar()
+    .and_then(|fvar| fvar.axes())
+    .map(|axes| axes.iter().map(VariationAxisRecord::axis_tag).collect())
+    .unwrap_or_default();
+  let mut positions: BTreeMap<String, Vec<f32>> = BTreeMap::new();
+  for value in values.axis_values().iter().filter_map(Result::ok) {
+    let (Some(index), Some(position)) = (value.axis_index(), value.value())
+    else {
+      continue;
+    };
+    let Some(axis) = design_axes.get(usize::from(index)) else {
+      continue;
+    };
+    if variable.contains(&axis.axis_tag()) {
+      continue;
+    }
+    let known = positions.entry(axis.axis_tag().to_string()).or_default();
+    if !known.contains(&position.to_f32()) {
+      known.push(position.to_f32());
+    }
+  }
+  positions
+    .into_iter()
+    .filter_map(|(tag, values)| match values[..] {
+      [position] => Some((tag, position)),
+      _ => None,
+    })
+    .collect()
+}
+
+/// An English name, or the first decodable one, as font menus show it.
+fn name(repr: &FontRepr, name_id: NameId) -> Option<String> {
+  FontNames::english(repr, name_id.to_u16())
+}
+
+/// The PostScript name of an instance without its own name record, built as
+/// Adobe Technical Note #5902 describes.
+fn post_script_name(repr: &FontRepr, subfamily: &str) -> Option<String> {
+  let prefix = [
+    name_id::VARIATIONS_POST_SCRIPT_NAME_PREFIX,
+    name_id::TYPOGRAPHIC_FAMILY,
+    name_id::FAMILY,
+  ]
+  .into_iter()
+  .find_map(|id| FontNames::english(repr, id))?;
+  let alphanumeric = |text: &str| -> String {
+    text.chars().filter(char::is_ascii_alphanumeric).collect()
+  };
+  Some(format!(
+    "{}-{}",
+    alphanumeric(&prefix),
+    alphanumeric(subfamily)
+  ))
+}
+
+/// The Typst variant at the given user space coordinates. Registered axes
+/// win over `OS/2` values, which describe the default instance only.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn variant(ttf: &Face, coordinates: &BTreeMap<String, f32>) -> FontVariant {
+  let weight = coordinates.get("wght").map_or_else(
+    || FontWeight::from_number(ttf.weight().to_number()),
+    |&weight| {
+      FontWeight::from_number(weight.round().clamp(1.0, 1000.0) as u16)
+    },
+  );
+  let stretch = coordinates.get("wdth").map_or_else(
+    || FontStretch::from_number(ttf.width().to_number()),
+    |&width| FontStretch::from_ratio(Ratio::new(f64::from(width) / 100.0)),
+  );
+  let style = match (coordinates.get("ital"), coordinates.get("slnt")) {
+    (Some(&italic), _) if italic >= 0.5 => FontStyle::Italic,
+    (_, Some(&slant)) if slant != 0.0 => FontStyle::Oblique,
+    (Some(_), _) | (_, Some(_)) => FontStyle::Normal,
+    _ => match ttf.style() {
+      Style::Normal => FontStyle::Normal,
+      Style::Italic => FontStyle::Italic,
+      Style::Oblique => FontStyle::Oblique,
+    },
+  };
+  FontVariant::new(style, weight, stretch)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct FontInstance {
+  // index of the font in the collection
+  face: u32,
+  // index into the instance list of `fvar`
+  index: u16,
+  subfamily: Option<String>,
+  post_script_name: Option<String>,
+  // user space coordinates keyed by axis tag, those of `STAT` included
+  coordinates: BTreeMap<String, f32>,
+  variant: FontVariant,
+}
+
+impl FontInstance {
+  /// Every named instance in `fvar` order, empty if the font is not
+  /// variable.
+  pub(crate) fn from_repr(repr: &FontRepr, face: u32) -> Vec<Self> {
+    let ttf = &repr.ttf_parser;
+    let Ok(fvar) = repr.font_ref.fvar() else {
+      return Vec::new();
+    };
+    let (Ok(axes), Ok(instances)) = (fvar.axes(), fvar.instances()) else {
+      return Vec::new();
+    };
+    let defaults = stat_coordinates(repr);
+    (0..)
+      .zip(instances.iter())
+      .filter_map(|(index, instance)| Some((index, instance.ok()?)))
+      .map(|(index, instance)| {
+        let mut coordinates = defaults.clone();
+        for (axis, position) in axes.iter().zip(instance.coordinates) {
+          let position: Fixed = position.get();
+          coordinates.insert(axis.axis_tag().to_string(), position.to_f32());
+        }
+        let subfamily = name(repr, instance.subfamily_name_id);
+        let post_script_name = instance
+          .post_script_name_id
+          .and_then(|id| name(repr, id))
+          .or_else(|| post_script_name(repr, subfamily.as_deref()?));
+        FontInstance {
+          face,
+          index,
+          subfamily,
+          post_script_name,
+          variant: variant(ttf, &coordinates),
+          coordinates,
+        }
+      })
+      .collect()
+  }
+}
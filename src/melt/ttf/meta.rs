@@ -0,0 +1,73 @@
+/// This mod reads the data maps of the `meta` table, metadata of the font
+/// keyed by tag, e.g. the languages it is designed for.
+/// - see: <https://learn.microsoft.com/en-us/typography/opentype/spec/meta>
+use crate::melt::bcp47::LanguageTag;
+use crate::melt::repr::FontRepr;
+use serde::{Deserialize, Serialize};
+use serde_bytes::ByteBuf;
+use skrifa::raw::tables::meta::{DLNG, SLNG};
+use skrifa::raw::types::Tag;
+use skrifa::raw::{Offset, TableProvider};
+use std::collections::BTreeMap;
+
+/// Data of each data map in table order, empty if the font has no `meta`
+/// table.
+pub(crate) struct DataMaps<'a>(Vec<(Tag, &'a [u8])>);
+
+impl<'a> DataMaps<'a> {
+  pub(crate) fn from_repr(repr: &FontRepr<'a>) -> Self {
+    let Ok(meta) = repr.font_ref.meta() else {
+      return DataMaps(Vec::new());
+    };
+    let data = meta.offset_data();
+    let maps = meta
+      .data_maps()
+      .iter()
+      .filter_map(|record| {
+        let offset = record.data_offset().to_usize();
+        let length = record.data_length() as usize;
+        let end = offset.checked_add(length)?;
+        let bytes = data.read_array::<u8>(offset..end).ok()?;
+        Some((record.tag(), bytes))
+      })
+      .collect();
+    DataMaps(maps)
+  }
+
+  /// `ScriptLangTag`s of `dlng` or `slng`, separated by commas with any
+  /// whitespace around them.
+  pub(crate) fn script_lang_tags(&self, tag: Tag) -> Vec<LanguageTag> {
+    self
+      .0
+      .iter()
+      .find(|(map_tag, _)| *map_tag == tag)
+      .and_then(|(_, bytes)| std::str::from_utf8(bytes).ok())
+      .map(|text| {
+        text
+          .split(',')
+          .map(str::trim)
+          .filter(|tag| !tag.is_empty())
+          .map(LanguageTag::parse)
+          .collect()
+      })
+      .unwrap_or_default()
+  }
+}
+
+/// Data maps other than `dlng` and `slng`, which are parsed in
+/// `FontScripts`, as raw bytes keyed by tag, e.g. `appl` or `bild`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct FontMeta(BTreeMap<String, ByteBuf>);
+
+impl FontMeta {
+  pub(crate) fn from_repr(repr: &FontRepr) -> Self {
+    FontMeta(
+      DataMaps::from_repr(repr)
+        .0
+        .into_iter()
+        .filter(|(tag, _)| *tag != DLNG && *tag != SLNG)
+        .map(|(tag, bytes)| (tag.to_string(), ByteBuf::from(bytes)))
+        .collect(),
+    )
+  }
+}
@@ -1,16 +1,23 @@
+mod codepage;
 mod encoding;
 mod language;
+mod resolve;
 
 use crate::melt::repr::FontRepr;
 use encoding::{MacintoshEncoding, PlatformEncoding};
 use language::MacintoshLanguage;
+pub(crate) use resolve::{NameResolveOptions, ResolvedName, resolve_names};
 use serde::{Deserialize, Serialize};
-use ttf_parser::{self, Face};
+use skrifa::raw::{Offset, TableProvider};
+use ttf_parser::{self, PlatformId};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct FontName {
   name: Option<String>,
   language: Option<String>,
+  // BCP 47 tag of the language, e.g. `en-US`, from the language id or a
+  // language-tag record of a format 1 `name` table
+  language_tag: Option<String>,
   platform_encoding: PlatformEncoding,
 }
 
@@ -43,41 +50,124 @@ impl FontName {
       .collect()
   }
 
-  fn from_ttf_name(name: ttf_parser::name::Name) -> Self {
+  /// `language_tags` are the language-tag records of the `name` table,
+  /// referred to by language ids from `0x8000` on.
+  fn from_ttf_name(
+    name: ttf_parser::name::Name,
+    language_tags: &[String],
+  ) -> Self {
     let platform_encoding =
       PlatformEncoding::from_indices(name.platform_id, name.encoding_id);
-    let language = match platform_encoding {
-      PlatformEncoding::Windows(_) => Some(name.language().to_string()),
+    let (language, language_tag) = match platform_encoding {
       PlatformEncoding::Macintosh(_) => {
-        MacintoshLanguage::from_index(name.language_id)
-          .map(|lang| lang.to_string())
+        let language = MacintoshLanguage::from_index(name.language_id);
+        (
+          language.map(|lang| lang.to_string()),
+          language.map(|lang| lang.tag().to_string()),
+        )
       }
-      _ => None,
+      _ if name.language_id >= 0x8000 => {
+        let tag = language_tags
+          .get(usize::from(name.language_id - 0x8000))
+          .cloned();
+        (tag.clone(), tag)
+      }
+      PlatformEncoding::Windows(_) => (
+        Some(name.language().to_string()),
+        language::windows_language_tag(name.language_id).map(str::to_string),
+      ),
+      _ => (None, None),
     };
-    let name = name.to_string().or(match platform_encoding {
+    let name = match platform_encoding {
       PlatformEncoding::Macintosh(MacintoshEncoding::Roman) => {
         Some(Self::decode_macos_roman(name.name))
       }
-      _ => None,
-    });
+      _ => codepage::decode(platform_encoding, name.name),
+    };
     FontName {
       name,
       language,
+      language_tag,
       platform_encoding,
     }
   }
 }
 
+/// Records of the `name` table. They are read with skrifa, as ttf-parser
+/// expects language-tag records of format 1 tables before the name records.
+struct NameTable<'a> {
+  // records paired with their index in the table
+  records: Vec<(u16, ttf_parser::name::Name<'a>)>,
+  // language-tag records of format 1 tables, e.g. `en` or `zh-Hant`
+  language_tags: Vec<String>,
+}
+
+impl<'a> NameTable<'a> {
+  fn from_repr(repr: &FontRepr<'a>) -> Self {
+    let Ok(name) = repr.font_ref.name() else {
+      return NameTable {
+        records: Vec::new(),
+        language_tags: Vec::new(),
+      };
+    };
+    let data = name.string_data();
+    let bytes = data.as_bytes();
+    let string = |offset: usize, length: u16| {
+      bytes.get(offset..offset + usize::from(length))
+    };
+    let records = name
+      .name_record()
+      .iter()
+      .zip(0..)
+      .filter_map(|(record, index)| {
+        let name = ttf_parser::name::Name {
+          platform_id: match record.platform_id() {
+            0 => PlatformId::Unicode,
+            1 => PlatformId::Macintosh,
+            2 => PlatformId::Iso,
+            3 => PlatformId::Windows,
+            4 => PlatformId::Custom,
+            _ => return None,
+          },
+          encoding_id: record.encoding_id(),
+          language_id: record.language_id(),
+          name_id: record.name_id().to_u16(),
+          name: string(record.string_offset().to_usize(), record.length())?,
+        };
+        Some((index, name))
+      })
+      .collect();
+    let language_tags = name
+      .lang_tag_record()
+      .map(|records| {
+        records
+          .iter()
+          .map(|record| {
+            record
+              .lang_tag(data)
+              .map(|tag| tag.chars().collect())
+              .unwrap_or_default()
+          })
+          .collect()
+      })
+      .unwrap_or_default();
+    NameTable {
+      records,
+      language_tags,
+    }
+  }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 struct FontNameVec(Vec<FontName>);
 
 impl FontNameVec {
-  fn from_ttf(ttf: &Face, name_id: u16) -> Self {
-    let name_vec: Vec<FontName> = ttf
-      .names()
-      .into_iter()
-      .filter(|entry| entry.name_id == name_id)
-      .map(FontName::from_ttf_name)
+  fn from_table(table: &NameTable, name_id: u16) -> Self {
+    let name_vec: Vec<FontName> = table
+      .records
+      .iter()
+      .filter(|(_, entry)| entry.name_id == name_id)
+      .map(|(_, entry)| FontName::from_ttf_name(*entry, &table.language_tags))
       .collect();
     FontNameVec(name_vec)
   }
@@ -151,15 +241,22 @@ impl FontNames {
     variations_post_script_name_prefix => VARIATIONS_POST_SCRIPT_NAME_PREFIX,
   ];
 
+  /// The name with id `name_id` in English, or the first decodable one if
+  /// there is no English record.
+  pub(crate) fn english(repr: &FontRepr, name_id: u16) -> Option<String> {
+    let table = NameTable::from_repr(repr);
+    resolve::best_name(&table, &[name_id], &[]).map(ResolvedName::into_name)
+  }
+
   #[allow(clippy::wildcard_imports)]
   pub(crate) fn from_repr(repr: &FontRepr) -> Self {
     use ttf_parser::name_id::*;
-    let ttf = &repr.ttf_parser;
+    let table = NameTable::from_repr(repr);
 
     macro_rules! make_font_names {
       ($fonts:ident, $($id:ident => $key:expr), * $(,)?) => {
         $fonts $(.$id(
-          $crate::melt::ttf::names::FontNameVec::from_ttf(ttf, $key)
+          $crate::melt::ttf::names::FontNameVec::from_table(&table, $key)
         ))*;
       };
     }
@@ -0,0 +1,165 @@
+use super::encoding::{MacintoshEncoding, PlatformEncoding, WindowsEncoding};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Characters of single and double byte codes, bytes below `0x80` not in
+/// the table are ASCII.
+type CodePage = HashMap<u16, &'static str>;
+
+/// Reads the tables generated by `scripts/codepages.py`.
+fn code_page(data: &'static str) -> CodePage {
+  let mut table = HashMap::new();
+  for line in data.lines().filter(|line| !line.starts_with('#')) {
+    let Some((code, text)) = line.split_once(';') else {
+      continue;
+    };
+    if let Some(code) = code.strip_suffix('+') {
+      if let Ok(code) = u16::from_str_radix(code, 16) {
+        table.insert(code, text);
+      }
+      continue;
+    }
+    let Ok(start) = u16::from_str_radix(code, 16) else {
+      continue;
+    };
+    for ((index, character), code) in text.char_indices().zip(start..) {
+      table.insert(code, &text[index..index + character.len_utf8()]);
+    }
+  }
+  table
+}
+
+macro_rules! code_pages {
+  ($($id:ident => $file:literal), * $(,)?) => {
+    $(static $id: LazyLock<CodePage> = LazyLock::new(|| {
+      code_page(include_str!(concat!("codepages/", $file)))
+    });)*
+  };
+}
+
+code_pages![
+  WINDOWS_SHIFT_JIS => "windows_shift_jis.txt",
+  WINDOWS_PRC => "windows_prc.txt",
+  WINDOWS_BIG5 => "windows_big5.txt",
+  WINDOWS_WANSUNG => "windows_wansung.txt",
+  WINDOWS_JOHAB => "windows_johab.txt",
+  MAC_JAPANESE => "mac_japanese.txt",
+  MAC_CHINESE_TRADITIONAL => "mac_chinese_traditional.txt",
+  MAC_KOREAN => "mac_korean.txt",
+  MAC_ARABIC => "mac_arabic.txt",
+  MAC_HEBREW => "mac_hebrew.txt",
+  MAC_GREEK => "mac_greek.txt",
+  MAC_RUSSIAN => "mac_russian.txt",
+  MAC_THAI => "mac_thai.txt",
+  MAC_CHINESE_SIMPLIFIED => "mac_chinese_simplified.txt",
+  MAC_SLAVIC => "mac_slavic.txt",
+  MAC_DEVANAGARI => "mac_devanagari.txt",
+  MAC_GURMUKHI => "mac_gurmukhi.txt",
+  MAC_GUJARATI => "mac_gujarati.txt",
+  MAC_SYMBOL => "mac_symbol.txt",
+];
+
+/// Decodes single and double byte codes, a lead byte followed by a trail
+/// byte, replacing unknown codes with U+FFFD. Double byte codes come first,
+/// as in Apple's Indic encodings a letter followed by the nukta is another
+/// letter, e.g. `0xB3 0xE9` is `U+0958` while `0xB3` is `U+0915`.
+fn decode_bytes(table: &CodePage, bytes: &[u8]) -> String {
+  let mut text = String::new();
+  let mut index = 0;
+  while let Some(&byte) = bytes.get(index) {
+    if let Some(double) = bytes
+      .get(index + 1)
+      .and_then(|&trail| table.get(&u16::from_be_bytes([byte, trail])))
+    {
+      text.push_str(double);
+      index += 2;
+    } else if let Some(single) = table.get(&u16::from(byte)) {
+      text.push_str(single);
+      index += 1;
+    } else {
+      text.push(if byte < 0x80 {
+        char::from(byte)
+      } else {
+        char::REPLACEMENT_CHARACTER
+      });
+      index += 1;
+    }
+  }
+  text
+}
+
+/// Windows records in legacy encodings are stored as 16-bit big endian
+/// values, single byte codes padded with a zero byte, as `FreeType` reads
+/// them. Records of odd length are taken as plain bytes.
+fn decode_windows(table: &CodePage, bytes: &[u8]) -> String {
+  if !bytes.len().is_multiple_of(2) {
+    return decode_bytes(table, bytes);
+  }
+  let unpadded: Vec<u8> = bytes
+    .chunks_exact(2)
+    .flat_map(|unit| match unit {
+      [0, low] => vec![*low],
+      [high, low] => vec![*high, *low],
+      _ => Vec::new(),
+    })
+    .collect();
+  decode_bytes(table, &unpadded)
+}
+
+fn decode_utf16_be(bytes: &[u8]) -> Option<String> {
+  let units: Vec<u16> = bytes
+    .chunks_exact(2)
+    .map(|unit| u16::from_be_bytes([unit[0], unit[1]]))
+    .collect();
+  String::from_utf16(&units).ok()
+}
+
+/// Decodes a record of the `name` table, `None` for encodings without a
+/// published mapping, e.g. Macintosh Bangla or Tamil, and for `Iso` or
+/// `Custom` platforms. Macintosh Roman is decoded by the caller.
+pub(super) fn decode(
+  platform_encoding: PlatformEncoding,
+  bytes: &[u8],
+) -> Option<String> {
+  let windows = |table: &CodePage| Some(decode_windows(table, bytes));
+  let macintosh = |table: &CodePage| Some(decode_bytes(table, bytes));
+  match platform_encoding {
+    PlatformEncoding::Unicode(_)
+    | PlatformEncoding::Windows(
+      WindowsEncoding::Symbol
+      | WindowsEncoding::UnicodeBMP
+      | WindowsEncoding::UnicodeFull,
+    ) => decode_utf16_be(bytes),
+    PlatformEncoding::Windows(encoding) => match encoding {
+      WindowsEncoding::ShiftJIS => windows(&WINDOWS_SHIFT_JIS),
+      WindowsEncoding::Rpc => windows(&WINDOWS_PRC),
+      WindowsEncoding::Big5 => windows(&WINDOWS_BIG5),
+      WindowsEncoding::Wansung => windows(&WINDOWS_WANSUNG),
+      WindowsEncoding::Johab => windows(&WINDOWS_JOHAB),
+      _ => None,
+    },
+    PlatformEncoding::Macintosh(encoding) => match encoding {
+      MacintoshEncoding::Japanese => macintosh(&MAC_JAPANESE),
+      MacintoshEncoding::ChineseTradational => {
+        macintosh(&MAC_CHINESE_TRADITIONAL)
+      }
+      MacintoshEncoding::Korean => macintosh(&MAC_KOREAN),
+      MacintoshEncoding::Arabic => macintosh(&MAC_ARABIC),
+      MacintoshEncoding::Hebrew => macintosh(&MAC_HEBREW),
+      MacintoshEncoding::Greek => macintosh(&MAC_GREEK),
+      MacintoshEncoding::Russian => macintosh(&MAC_RUSSIAN),
+      MacintoshEncoding::Thai => macintosh(&MAC_THAI),
+      MacintoshEncoding::ChineseSimplified => {
+        macintosh(&MAC_CHINESE_SIMPLIFIED)
+      }
+      MacintoshEncoding::Slavic => macintosh(&MAC_SLAVIC),
+      MacintoshEncoding::Devanagari => macintosh(&MAC_DEVANAGARI),
+      MacintoshEncoding::Gurmukhi => macintosh(&MAC_GURMUKHI),
+      MacintoshEncoding::Gujarati => macintosh(&MAC_GUJARATI),
+      // the only symbol encoding Apple published a mapping of
+      MacintoshEncoding::RSymbol => macintosh(&MAC_SYMBOL),
+      _ => None,
+    },
+    PlatformEncoding::Iso(_) | PlatformEncoding::Custom(_) => None,
+  }
+}
@@ -0,0 +1,231 @@
+/// This mod picks the one name a font should be shown under for each name
+/// id, from the records in every platform and language of the `name` table.
+use super::encoding::PlatformEncoding;
+use super::{FontName, NameTable};
+use crate::melt::repr::FontRepr;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use ttf_parser::PlatformId;
+use ttf_parser::name_id::{
+  COMPATIBLE_FULL, COPYRIGHT_NOTICE, DARK_BACKGROUND_PALETTE, DESCRIPTION,
+  DESIGNER, DESIGNER_URL, FAMILY, FULL_NAME, LICENSE, LICENSE_URL,
+  LIGHT_BACKGROUND_PALETTE, MANUFACTURER, POST_SCRIPT_CID, POST_SCRIPT_NAME,
+  SAMPLE_TEXT, SUBFAMILY, TRADEMARK, TYPOGRAPHIC_FAMILY,
+  TYPOGRAPHIC_SUBFAMILY, UNIQUE_ID, VARIATIONS_POST_SCRIPT_NAME_PREFIX,
+  VENDOR_URL, VERSION, WWS_FAMILY, WWS_SUBFAMILY,
+};
+
+/// Language tried after the preferred ones.
+const DEFAULT_LANGUAGE: &str = "en-US";
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum NamingStrategy {
+  // typographic family and subfamily (ids 16 and 17), falling back to the
+  // family and subfamily (ids 1 and 2), as most font menus group fonts
+  #[default]
+  Typographic,
+  // WWS family and subfamily (ids 21 and 22), falling back to typographic
+  // ones, for applications grouping by weight, width and slope only
+  Wws,
+  // family and subfamily (ids 1 and 2), at most four styles per family
+  Legacy,
+  // the family Typst registers the font under, subfamilies as typographic
+  Typst,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct NameResolveOptions {
+  // BCP 47 tags in order of preference, `en-US` is tried after them
+  pub(crate) languages: Vec<String>,
+  pub(crate) strategy: NamingStrategy,
+}
+
+/// The record a name is read from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PickedRecord {
+  // index of the record in the `name` table
+  index: u16,
+  // differs from the requested id after a fallback, e.g. `1` for a
+  // typographic family of a font without name id 16
+  name_id: u16,
+  language: Option<String>,
+  language_tag: Option<String>,
+  platform_encoding: PlatformEncoding,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ResolvedName {
+  name: String,
+  // `None` for names not read from a single record, e.g. Typst's family
+  record: Option<PickedRecord>,
+}
+
+impl ResolvedName {
+  pub(crate) fn into_name(self) -> String {
+    self.name
+  }
+}
+
+/// Language ranges in order of preference, each tag followed by its
+/// truncations as in BCP 47 lookup, e.g. `zh-hant-tw`, `zh-hant`, `zh`.
+fn language_ranges(languages: &[String]) -> Vec<String> {
+  let mut ranges: Vec<String> = Vec::new();
+  for language in languages
+    .iter()
+    .map(String::as_str)
+    .chain([DEFAULT_LANGUAGE])
+  {
+    let mut range = language.to_ascii_lowercase();
+    loop {
+      if !range.is_empty() && !ranges.contains(&range) {
+        ranges.push(range.clone());
+      }
+      let Some(end) = range.rfind('-') else {
+        break;
+      };
+      range.truncate(end);
+    }
+  }
+  ranges
+}
+
+/// Position of the first range matching the tag, a range matches the tag
+/// itself and tags it is a prefix of, e.g. `de` matches `de-CH`.
+fn language_rank(tag: Option<&str>, ranges: &[String]) -> usize {
+  let Some(tag) = tag.map(str::to_ascii_lowercase) else {
+    return usize::MAX;
+  };
+  ranges
+    .iter()
+    .position(|range| {
+      tag == *range
+        || tag
+          .strip_prefix(range.as_str())
+          .is_some_and(|rest| rest.starts_with('-'))
+    })
+    .unwrap_or(usize::MAX)
+}
+
+/// Windows records are the most reliable, Macintosh ones the least.
+fn platform_rank(platform_id: PlatformId) -> u8 {
+  match platform_id {
+    PlatformId::Windows => 0,
+    PlatformId::Unicode => 1,
+    PlatformId::Macintosh => 2,
+    PlatformId::Iso | PlatformId::Custom => 3,
+  }
+}
+
+/// The best decodable record of the first name id in `name_ids` having
+/// one, by language, then platform, then table order.
+pub(super) fn best_name(
+  table: &NameTable,
+  name_ids: &[u16],
+  languages: &[String],
+) -> Option<ResolvedName> {
+  let ranges = language_ranges(languages);
+  name_ids.iter().find_map(|&name_id| {
+    table
+      .records
+      .iter()
+      .filter(|(_, record)| record.name_id == name_id)
+      .filter_map(|&(index, record)| {
+        let name = FontName::from_ttf_name(record, &table.language_tags);
+        let rank = (
+          language_rank(name.language_tag.as_deref(), &ranges),
+          platform_rank(record.platform_id),
+          index,
+        );
+        Some((
+          rank,
+          index,
+          name.name.clone().filter(|n| !n.is_empty())?,
+          name,
+        ))
+      })
+      .min_by_key(|&(rank, ..)| rank)
+      .map(|(_, index, text, name)| ResolvedName {
+        name: text,
+        record: Some(PickedRecord {
+          index,
+          name_id,
+          language: name.language,
+          language_tag: name.language_tag,
+          platform_encoding: name.platform_encoding,
+        }),
+      })
+  })
+}
+
+/// The best name for each name id found, keyed as in `FontNames`.
+pub(crate) fn resolve_names(
+  repr: &FontRepr,
+  options: &NameResolveOptions,
+) -> BTreeMap<&'static str, ResolvedName> {
+  let (family, subfamily): (&[u16], &[u16]) = match options.strategy {
+    NamingStrategy::Typographic | NamingStrategy::Typst => (
+      &[TYPOGRAPHIC_FAMILY, FAMILY],
+      &[TYPOGRAPHIC_SUBFAMILY, SUBFAMILY],
+    ),
+    NamingStrategy::Wws => (
+      &[WWS_FAMILY, TYPOGRAPHIC_FAMILY, FAMILY],
+      &[WWS_SUBFAMILY, TYPOGRAPHIC_SUBFAMILY, SUBFAMILY],
+    ),
+    NamingStrategy::Legacy => (&[FAMILY], &[SUBFAMILY]),
+  };
+  let name_ids: [(&str, &[u16]); 25] = [
+    ("copyright_notice", &[COPYRIGHT_NOTICE]),
+    ("family", family),
+    ("subfamily", subfamily),
+    ("unique_id", &[UNIQUE_ID]),
+    ("full_name", &[FULL_NAME]),
+    ("version", &[VERSION]),
+    ("post_script_name", &[POST_SCRIPT_NAME]),
+    ("trademark", &[TRADEMARK]),
+    ("manufacturer", &[MANUFACTURER]),
+    ("designer", &[DESIGNER]),
+    ("description", &[DESCRIPTION]),
+    ("vendor_url", &[VENDOR_URL]),
+    ("designer_url", &[DESIGNER_URL]),
+    ("license", &[LICENSE]),
+    ("license_url", &[LICENSE_URL]),
+    ("typographic_family", &[TYPOGRAPHIC_FAMILY, FAMILY]),
+    ("typographic_subfamily", &[TYPOGRAPHIC_SUBFAMILY, SUBFAMILY]),
+    ("compatible_full", &[COMPATIBLE_FULL, FULL_NAME]),
+    ("sample_text", &[SAMPLE_TEXT]),
+    ("post_script_cid", &[POST_SCRIPT_CID]),
+    ("wws_family", &[WWS_FAMILY, TYPOGRAPHIC_FAMILY, FAMILY]),
+    (
+      "wws_subfamily",
+      &[WWS_SUBFAMILY, TYPOGRAPHIC_SUBFAMILY, SUBFAMILY],
+    ),
+    ("light_background_palette", &[LIGHT_BACKGROUND_PALETTE]),
+    ("dark_background_palette", &[DARK_BACKGROUND_PALETTE]),
+    (
+      "variations_post_script_name_prefix",
+      &[VARIATIONS_POST_SCRIPT_NAME_PREFIX],
+    ),
+  ];
+  let table = NameTable::from_repr(repr);
+  let mut names: BTreeMap<&'static str, ResolvedName> = name_ids
+    .into_iter()
+    .filter_map(|(key, ids)| {
+      Some((key, best_name(&table, ids, &options.languages)?))
+    })
+    .collect();
+  if let NamingStrategy::Typst = options.strategy
+    && let Some(info) = repr.info_without_coverage()
+    && !info.family.is_empty()
+  {
+    names.insert(
+      "family",
+      ResolvedName {
+        name: info.family.clone(),
+        record: None,
+      },
+    );
+  }
+  names
+}
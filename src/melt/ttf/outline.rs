@@ -0,0 +1,395 @@
+/// This mod provides glyph outlines as structured path commands, in font
+/// units with y axis pointing up.
+use crate::melt::repr::FontRepr;
+use serde::{Deserialize, Serialize};
+use ttf_parser::{GlyphId, OutlineBuilder};
+
+use super::glyphs::{BBox, GlyphInfo};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum PathOp {
+  Move,
+  Line,
+  Quad,
+  Cubic,
+  Close,
+}
+
+/// A path command, `points` are control points followed by the end point,
+/// each one is `[x, y]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct PathCommand {
+  pub(crate) op: PathOp,
+  pub(crate) points: Vec<[f32; 2]>,
+}
+
+/// A closed (or open) sub-path, always starts with a `move`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Contour(pub(crate) Vec<PathCommand>);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Outline(pub(crate) Vec<Contour>);
+
+impl OutlineBuilder for Outline {
+  fn move_to(&mut self, x: f32, y: f32) {
+    self.0.push(Contour(vec![PathCommand {
+      op: PathOp::Move,
+      points: vec![[x, y]],
+    }]));
+  }
+
+  fn line_to(&mut self, x: f32, y: f32) {
+    self.push(PathOp::Line, vec![[x, y]]);
+  }
+
+  fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+    self.push(PathOp::Quad, vec![[x1, y1], [x, y]]);
+  }
+
+  fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+    self.push(PathOp::Cubic, vec![[x1, y1], [x2, y2], [x, y]]);
+  }
+
+  fn close(&mut self) {
+    self.push(PathOp::Close, vec![]);
+  }
+}
+
+impl Outline {
+  fn push(&mut self, op: PathOp, points: Vec<[f32; 2]>) {
+    if self.0.is_empty() {
+      // a well-formed outline always moves first, be lenient anyway
+      self.0.push(Contour::default());
+    }
+    if let Some(contour) = self.0.last_mut() {
+      contour.0.push(PathCommand { op, points });
+    }
+  }
+
+  pub(crate) fn from_glyph_id(
+    repr: &FontRepr,
+    glyph_id: GlyphId,
+  ) -> Option<Self> {
+    let mut outline = Outline::default();
+    repr.ttf_parser.outline_glyph(glyph_id, &mut outline)?;
+    Some(outline)
+  }
+
+  /// Elevates quadratic Béziers to the equivalent cubic ones.
+  pub(crate) fn into_cubic(mut self) -> Self {
+    for contour in &mut self.0 {
+      let mut current = [0.0, 0.0];
+      for command in &mut contour.0 {
+        if command.op == PathOp::Quad {
+          let ([cx, cy], [x, y]) = (command.points[0], command.points[1]);
+          let [x0, y0] = current;
+          command.op = PathOp::Cubic;
+          command.points = vec![
+            [x0 + 2.0 / 3.0 * (cx - x0), y0 + 2.0 / 3.0 * (cy - y0)],
+            [x + 2.0 / 3.0 * (cx - x), y + 2.0 / 3.0 * (cy - y)],
+            [x, y],
+          ];
+        }
+        if let Some(&end) = command.points.last() {
+          current = end;
+        }
+      }
+    }
+    self
+  }
+}
+
+impl Outline {
+  fn points(&self) -> impl Iterator<Item = &[f32; 2]> {
+    self
+      .0
+      .iter()
+      .flat_map(|contour| &contour.0)
+      .flat_map(|command| &command.points)
+  }
+
+  fn transform(&mut self, f: impl Fn([f32; 2]) -> [f32; 2]) {
+    for contour in &mut self.0 {
+      for command in &mut contour.0 {
+        for point in &mut command.points {
+          *point = f(*point);
+        }
+      }
+    }
+  }
+
+  pub(crate) fn scale(&mut self, factor: f32) {
+    self.transform(|[x, y]| [x * factor, y * factor]);
+  }
+
+  pub(crate) fn translate(&mut self, dx: f32, dy: f32) {
+    self.transform(|[x, y]| [x + dx, y + dy]);
+  }
+
+  /// Moves all contours of `other` into this outline.
+  pub(crate) fn append(&mut self, other: Outline) {
+    self.0.extend(other.0);
+  }
+
+  /// Bounding box of all points, including off-curve ones, like the one
+  /// `ttf_parser` reports.
+  pub(crate) fn bbox(&self) -> Option<BBox<f32>> {
+    self.points().fold(None, |bbox, &[x, y]| {
+      Some(match bbox {
+        None => BBox {
+          x_min: x,
+          y_min: y,
+          x_max: x,
+          y_max: y,
+        },
+        Some(bbox) => BBox {
+          x_min: bbox.x_min.min(x),
+          y_min: bbox.y_min.min(y),
+          x_max: bbox.x_max.max(x),
+          y_max: bbox.y_max.max(y),
+        },
+      })
+    })
+  }
+
+  /// Twice the signed area of the control polygons, positive if outer
+  /// contours run counterclockwise (PostScript), negative if clockwise
+  /// (TrueType).
+  fn signed_area(&self) -> f32 {
+    self
+      .0
+      .iter()
+      .map(|contour| {
+        let points: Vec<[f32; 2]> = contour
+          .0
+          .iter()
+          .flat_map(|command| command.points.iter().copied())
+          .collect();
+        points
+          .iter()
+          .zip(points.iter().cycle().skip(1))
+          .map(|([x0, y0], [x1, y1])| x0 * y1 - x1 * y0)
+          .sum::<f32>()
+      })
+      .sum()
+  }
+
+  /// Offsets every contour outwards by `strength / 2`, then moves the
+  /// outline right by `strength / 2` to keep the left side bearing.
+  ///
+  /// Port of `FT_Outline_EmboldenXY` from `FreeType`, points (including
+  /// off-curve ones) are moved along the bisector of their adjacent edges,
+  /// at most as far as the shorter edge is long.
+  fn embolden(&mut self, strength: f32) {
+    // unit vector and length of a segment
+    fn direction(from: [f32; 2], to: [f32; 2]) -> Option<([f32; 2], f32)> {
+      let (dx, dy) = (to[0] - from[0], to[1] - from[1]);
+      let length = dx.hypot(dy);
+      (length > f32::EPSILON).then(|| ([dx / length, dy / length], length))
+    }
+
+    let half = strength / 2.0;
+    let clockwise = self.signed_area() < 0.0;
+    for contour in &mut self.0 {
+      let mut ring: Vec<[f32; 2]> = contour
+        .0
+        .iter()
+        .flat_map(|command| command.points.iter().copied())
+        .collect();
+      // an explicit segment back to the start duplicates the first point
+      let closing = ring.len() > 1 && ring.first() == ring.last();
+      if closing {
+        ring.pop();
+      }
+      let count = ring.len();
+      let shifts: Vec<[f32; 2]> = (0..count)
+        .map(|i| {
+          let current = ring[i];
+          let incoming = (1..count).find_map(|step| {
+            direction(ring[(i + count - step) % count], current)
+          });
+          let outgoing = (1..count)
+            .find_map(|step| direction(current, ring[(i + step) % count]));
+          let (Some((a, length_in)), Some((b, length_out))) =
+            (incoming, outgoing)
+          else {
+            return [0.0, 0.0];
+          };
+          let cos = a[0] * b[0] + a[1] * b[1];
+          // hairpin turns are left untouched
+          if cos <= -0.9375 {
+            return [0.0, 0.0];
+          }
+          let d = cos + 1.0;
+          let mut shift = [a[1] + b[1], a[0] + b[0]];
+          let mut sin = b[0] * a[1] - b[1] * a[0];
+          if clockwise {
+            shift[0] = -shift[0];
+            sin = -sin;
+          } else {
+            shift[1] = -shift[1];
+          }
+          // limit the miter of sharp corners and short segments
+          let length = length_in.min(length_out);
+          let factor = if half * sin <= length * d {
+            half / d
+          } else {
+            length / sin
+          };
+          [shift[0] * factor, shift[1] * factor]
+        })
+        .collect();
+
+      let mut index = 0;
+      for command in &mut contour.0 {
+        for point in &mut command.points {
+          let shift = shifts[if index < count { index } else { 0 }];
+          point[0] += shift[0];
+          point[1] += shift[1];
+          index += 1;
+        }
+      }
+    }
+    self.transform(|[x, y]| [x + half, y]);
+  }
+
+  /// Feeds the commands into another builder, e.g. an SVG path writer.
+  pub(crate) fn replay(&self, builder: &mut impl OutlineBuilder) {
+    for command in self.0.iter().flat_map(|contour| &contour.0) {
+      match (command.op, command.points.as_slice()) {
+        (PathOp::Move, &[[x, y]]) => builder.move_to(x, y),
+        (PathOp::Line, &[[x, y]]) => builder.line_to(x, y),
+        (PathOp::Quad, &[[x1, y1], [x, y]]) => builder.quad_to(x1, y1, x, y),
+        (PathOp::Cubic, &[[x1, y1], [x2, y2], [x, y]]) => {
+          builder.curve_to(x1, y1, x2, y2, x, y);
+        }
+        (PathOp::Close, _) => builder.close(),
+        _ => {}
+      }
+    }
+  }
+}
+
+/// Synthetic styles applied to outlines, in order: emboldening, then
+/// extension, then obliquing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Synthesis {
+  // thickening of stems in font units, also added to the advance
+  pub(crate) embolden: f32,
+  // slant angle in degrees, positive leans to the right, the advance is
+  // kept
+  pub(crate) oblique: f32,
+  // horizontal scale, < 1 condenses and > 1 extends, also scales the
+  // advance
+  pub(crate) extend: f32,
+}
+
+impl Default for Synthesis {
+  fn default() -> Self {
+    Synthesis {
+      embolden: 0.0,
+      oblique: 0.0,
+      extend: 1.0,
+    }
+  }
+}
+
+impl Synthesis {
+  #[allow(clippy::float_cmp)]
+  pub(crate) fn is_identity(&self) -> bool {
+    self.embolden == 0.0 && self.oblique == 0.0 && self.extend == 1.0
+  }
+
+  pub(crate) fn apply(&self, outline: &mut Outline) {
+    if self.embolden != 0.0 {
+      outline.embolden(self.embolden);
+    }
+    let (extend, slant) = (self.extend, self.oblique.to_radians().tan());
+    outline.transform(|[x, y]| [x * extend + y * slant, y]);
+  }
+
+  /// Where a point of the unstyled outline ends up, e.g. an anchor, the
+  /// glyph is shifted right by half of the emboldening.
+  pub(crate) fn point(&self, [x, y]: [f32; 2]) -> [f32; 2] {
+    let slant = self.oblique.to_radians().tan();
+    [(x + self.embolden / 2.0) * self.extend + y * slant, y]
+  }
+
+  pub(crate) fn advance(&self, advance: f32) -> f32 {
+    (advance + self.embolden) * self.extend
+  }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct OutlineOptions {
+  // convert quadratic Béziers to cubic ones
+  pub(crate) cubic: bool,
+  pub(crate) synthesis: Synthesis,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct GlyphOutline {
+  id: u16,
+  advance: Option<f32>,
+  bbox: Option<BBox<f32>>,
+  contours: Outline,
+}
+
+impl GlyphOutline {
+  pub(crate) fn from_glyph(
+    repr: &FontRepr,
+    glyph: &GlyphInfo,
+    options: &OutlineOptions,
+  ) -> Self {
+    let ttf = &repr.ttf_parser;
+    let glyph_id = glyph.id();
+    // glyphs without outline (e.g. space) get an empty contour list
+    let mut outline =
+      Outline::from_glyph_id(repr, glyph_id).unwrap_or_default();
+    let synthesis = &options.synthesis;
+    let bbox = if synthesis.is_identity() {
+      ttf.glyph_bounding_box(glyph_id).map(BBox::from)
+    } else {
+      synthesis.apply(&mut outline);
+      outline.bbox()
+    };
+    if options.cubic {
+      outline = outline.into_cubic();
+    }
+    GlyphOutline {
+      id: glyph_id.0,
+      advance: ttf
+        .glyph_hor_advance(glyph_id)
+        .map(|advance| synthesis.advance(f32::from(advance))),
+      bbox,
+      contours: outline,
+    }
+  }
+
+  pub(crate) fn from_character(
+    repr: &FontRepr,
+    ch: char,
+    options: &OutlineOptions,
+  ) -> Option<Self> {
+    let glyph = GlyphInfo::from_character(repr, ch)?;
+    Some(Self::from_glyph(repr, &glyph, options))
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct GlyphsOutlines(Vec<Option<GlyphOutline>>);
+
+impl GlyphsOutlines {
+  pub(crate) fn from_option_iter(
+    repr: &FontRepr,
+    codes: impl Iterator<Item = Option<char>>,
+    options: &OutlineOptions,
+  ) -> Self {
+    let outlines =
+      codes.map(|code| GlyphOutline::from_character(repr, code?, options));
+    GlyphsOutlines(outlines.collect())
+  }
+}
@@ -1,64 +1,16 @@
+mod registry;
+
+use registry::{LanguageTagInfo, ScriptTagInfo};
+pub(crate) use registry::{OpenTypeTags, script_tags};
 use serde::{Deserialize, Serialize};
-use skrifa::FontRef;
-use skrifa::raw::tables::meta::DataMapRecord;
-use skrifa::raw::{Offset, TableProvider, tables};
+use skrifa::raw::tables::meta::{DLNG, SLNG};
 use std::collections::BTreeSet;
 use ttf_parser::opentype_layout;
 
+use super::meta::DataMaps;
+use crate::melt::bcp47::LanguageTag;
 use crate::melt::repr::FontRepr;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct DesignedLanguages(BTreeSet<String>);
-
-impl DesignedLanguages {
-  pub fn from_skrifa_ref(font: &FontRef) -> Option<Self> {
-    let meta = font.meta().ok()?;
-    let data = meta.offset_data();
-    let tag_data = |record: &DataMapRecord| {
-      let offset = record.data_offset().to_usize();
-      let length = record.data_length() as usize;
-      data.read_array::<u8>(offset..offset + length).ok()
-    };
-    meta
-      .data_maps()
-      .iter()
-      .find(|record| record.tag == tables::meta::DLNG)
-      .and_then(|record| {
-        tag_data(record).and_then(|bytes| {
-          std::str::from_utf8(bytes).ok().map(|str| {
-            DesignedLanguages(str.split(", ").map(String::from).collect())
-          })
-        })
-      })
-  }
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub(super) struct SupportedLanguages(BTreeSet<String>);
-
-impl SupportedLanguages {
-  pub fn from_skrifa_ref(font: &FontRef) -> Option<Self> {
-    let meta = font.meta().ok()?;
-    let data = meta.offset_data();
-    let tag_data = |record: &DataMapRecord| {
-      let offset = record.data_offset().to_usize();
-      let length = record.data_length() as usize;
-      data.read_array::<u8>(offset..offset + length).ok()
-    };
-    meta
-      .data_maps()
-      .iter()
-      .find(|record| record.tag == tables::meta::SLNG)
-      .and_then(|record| {
-        tag_data(record).and_then(|bytes| {
-          std::str::from_utf8(bytes).ok().map(|str| {
-            SupportedLanguages(str.split(", ").map(String::from).collect())
-          })
-        })
-      })
-  }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct FontScript {
   tag: String,
@@ -88,8 +40,12 @@ impl FontScript {
 pub(crate) struct FontScripts {
   scripts: BTreeSet<String>,
   languages: BTreeSet<String>,
-  designed: DesignedLanguages,
-  supported: SupportedLanguages,
+  // registered names and codes of `scripts` and `languages`, in order
+  script_tags: Vec<ScriptTagInfo>,
+  language_tags: Vec<LanguageTagInfo>,
+  // `ScriptLangTag`s of the `meta` table, see `LanguageTag`
+  designed: Vec<LanguageTag>,
+  supported: Vec<LanguageTag>,
 }
 
 impl FontScripts {
@@ -107,24 +63,33 @@ impl FontScripts {
     let tables = repr.ttf_parser.tables();
     let gpos = tables.gpos.map_or(vec![], Self::scripts_from_layout_table);
     let gsub = tables.gsub.map_or(vec![], Self::scripts_from_layout_table);
-    let scripts = gpos
+    let scripts: BTreeSet<String> = gpos
       .clone()
       .into_iter()
       .map(|script| script.tag)
       .chain(gsub.clone().into_iter().map(|script| script.tag))
       .collect();
-    let languages = gpos
+    let languages: BTreeSet<String> = gpos
       .into_iter()
       .flat_map(|script| script.languages)
       .chain(gsub.into_iter().flat_map(|script| script.languages))
       .collect();
+    let maps = DataMaps::from_repr(repr);
+    let script_tags = scripts
+      .iter()
+      .map(|tag| ScriptTagInfo::from_tag(tag))
+      .collect();
+    let language_tags = languages
+      .iter()
+      .map(|tag| LanguageTagInfo::from_tag(tag))
+      .collect();
     FontScripts {
       scripts,
       languages,
-      designed: DesignedLanguages::from_skrifa_ref(&repr.font_ref)
-        .unwrap_or_default(),
-      supported: SupportedLanguages::from_skrifa_ref(&repr.font_ref)
-        .unwrap_or_default(),
+      script_tags,
+      language_tags,
+      designed: maps.script_lang_tags(DLNG),
+      supported: maps.script_lang_tags(SLNG),
     }
   }
 }
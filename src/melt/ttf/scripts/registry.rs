@@ -0,0 +1,261 @@
+/// This mod describes OpenType script and language system tags, e.g. `dev2`
+/// or `TRK `, and finds the tags a shaper selects for a BCP 47 language
+/// tag. The tables in `registry/` are generated by `scripts/ottags.py`.
+/// - see: <https://learn.microsoft.com/en-us/typography/opentype/spec/scripttags>
+/// - see: <https://learn.microsoft.com/en-us/typography/opentype/spec/languagetags>
+use crate::melt::bcp47::LanguageTag;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+struct ScriptRecord {
+  tag: &'static str,
+  name: &'static str,
+  iso_15924: Vec<&'static str>,
+  superseded_by: Option<&'static str>,
+}
+
+struct LanguageRecord {
+  tag: &'static str,
+  name: &'static str,
+  iso_639: Vec<&'static str>,
+  bcp47: Vec<&'static str>,
+  deprecated: bool,
+}
+
+fn words(field: &'static str) -> Vec<&'static str> {
+  field.split(' ').filter(|word| !word.is_empty()).collect()
+}
+
+/// Records sorted by tag.
+static SCRIPTS: LazyLock<Vec<ScriptRecord>> = LazyLock::new(|| {
+  include_str!("registry/scripts.txt")
+    .lines()
+    .filter(|line| !line.starts_with('#'))
+    .filter_map(|line| {
+      let mut fields = line.splitn(4, ';');
+      Some(ScriptRecord {
+        tag: fields.next()?,
+        name: fields.next()?,
+        iso_15924: words(fields.next()?),
+        superseded_by: fields.next().filter(|tag| !tag.is_empty()),
+      })
+    })
+    .collect()
+});
+
+/// Records sorted by tag.
+static LANGUAGES: LazyLock<Vec<LanguageRecord>> = LazyLock::new(|| {
+  include_str!("registry/languages.txt")
+    .lines()
+    .filter(|line| !line.starts_with('#'))
+    .filter_map(|line| {
+      let mut fields = line.splitn(5, ';');
+      Some(LanguageRecord {
+        tag: fields.next()?,
+        name: fields.next()?,
+        iso_639: words(fields.next()?),
+        bcp47: words(fields.next()?),
+        deprecated: fields.next()? == "1",
+      })
+    })
+    .collect()
+});
+
+fn strings(codes: &[&str]) -> Vec<String> {
+  codes.iter().copied().map(str::to_string).collect()
+}
+
+/// Tags are four bytes, shorter ones are padded with spaces, e.g. `lao `.
+fn padded(tag: &str) -> String {
+  format!("{tag:<4}")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ScriptTagInfo {
+  tag: String,
+  // e.g. `Devanagari v.2`, `None` if the tag is not registered
+  name: Option<String>,
+  // e.g. `["Hira", "Kana"]` for `kana`
+  iso_15924: Vec<String>,
+  // the tag of the newer shaping model, e.g. `dev2` for `deva`
+  superseded_by: Option<String>,
+}
+
+impl ScriptTagInfo {
+  pub(crate) fn from_tag(tag: &str) -> Self {
+    let padded = padded(tag);
+    let record = SCRIPTS
+      .binary_search_by(|record| record.tag.cmp(&padded))
+      .ok()
+      .map(|index| &SCRIPTS[index]);
+    ScriptTagInfo {
+      tag: tag.to_string(),
+      name: record.map(|record| record.name.to_string()),
+      iso_15924: record
+        .map_or_else(Vec::new, |record| strings(&record.iso_15924)),
+      superseded_by: record
+        .and_then(|record| record.superseded_by)
+        .map(str::to_string),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct LanguageTagInfo {
+  tag: String,
+  // e.g. `Turkish`, `None` if the tag is not registered
+  name: Option<String>,
+  // e.g. `["tur"]`
+  iso_639: Vec<String>,
+  // the shortest ISO 639 codes, as BCP 47 uses, e.g. `["tr"]`
+  bcp47: Vec<String>,
+  deprecated: bool,
+}
+
+impl LanguageTagInfo {
+  pub(crate) fn from_tag(tag: &str) -> Self {
+    let padded = padded(tag);
+    let record = LANGUAGES
+      .binary_search_by(|record| record.tag.cmp(&padded))
+      .ok()
+      .map(|index| &LANGUAGES[index]);
+    LanguageTagInfo {
+      tag: tag.to_string(),
+      name: record.map(|record| record.name.to_string()),
+      iso_639: record
+        .map_or_else(Vec::new, |record| strings(&record.iso_639)),
+      bcp47: record.map_or_else(Vec::new, |record| strings(&record.bcp47)),
+      deprecated: record.is_some_and(|record| record.deprecated),
+    }
+  }
+}
+
+/// Script tags of an ISO 15924 code, those of newer shaping models first,
+/// e.g. `dev2` and `deva` for `Deva`.
+pub(crate) fn script_tags(code: &str) -> Vec<String> {
+  let mut records: Vec<&ScriptRecord> = SCRIPTS
+    .iter()
+    .filter(|record| {
+      record
+        .iso_15924
+        .iter()
+        .any(|iso| iso.eq_ignore_ascii_case(code))
+    })
+    .collect();
+  records.sort_by_key(|record| record.superseded_by.is_some());
+  records
+    .into_iter()
+    .map(|record| record.tag.to_string())
+    .collect()
+}
+
+/// Language system tags listing a BCP 47 language subtag, those listing
+/// the fewest languages first, e.g. `ACR ` (Achi) before `MYN ` (Mayan).
+fn language_tags(language: &str) -> Vec<&'static LanguageRecord> {
+  let mut records: Vec<&LanguageRecord> = LANGUAGES
+    .iter()
+    .filter(|record| record.bcp47.contains(&language))
+    .collect();
+  records.sort_by_key(|record| record.iso_639.len());
+  records
+}
+
+/// Tags spelled out in private use subtags, e.g. `dev2` of `x-hbscdev2`,
+/// as `HarfBuzz` reads them.
+fn private_use_tag(tag: &LanguageTag, prefix: &str) -> Option<String> {
+  tag.private_use.as_deref()?.split('-').find_map(|subtag| {
+    let tag = subtag.strip_prefix(prefix)?;
+    (!tag.is_empty() && tag.len() <= 4).then(|| padded(tag))
+  })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct OpenTypeTags {
+  // as given, e.g. `zh-Hant-HK`
+  language: String,
+  // in order of preference, e.g. `["hani"]`
+  scripts: Vec<String>,
+  // in order of preference, e.g. `["ZHH "]`
+  languages: Vec<String>,
+}
+
+impl OpenTypeTags {
+  /// Follows `HarfBuzz`: subtags naming a script variant or a phonetic
+  /// notation select their own language system, Chinese is told apart by
+  /// script and region, other languages select the tags listing them.
+  pub(crate) fn from_language(language: &str) -> Self {
+    let tag = LanguageTag::parse(language);
+    let scripts = private_use_tag(&tag, "hbsc").map_or_else(
+      || tag.script.as_deref().map(script_tags).unwrap_or_default(),
+      |script| vec![script.to_ascii_lowercase()],
+    );
+    let languages = private_use_tag(&tag, "hbot").map_or_else(
+      || Self::language_system_tags(&tag),
+      |language| vec![language.to_ascii_uppercase()],
+    );
+    OpenTypeTags {
+      language: language.to_string(),
+      scripts,
+      languages,
+    }
+  }
+
+  fn language_system_tags(tag: &LanguageTag) -> Vec<String> {
+    let special = match tag.script.as_deref() {
+      Some("Geok") => Some("KGE "),
+      Some("Syre") => Some("SYRE"),
+      Some("Syrj") => Some("SYRJ"),
+      Some("Syrn") => Some("SYRN"),
+      _ => None,
+    }
+    .or_else(|| {
+      tag
+        .variants
+        .iter()
+        .find_map(|variant| match variant.as_str() {
+          "fonipa" => Some("IPPH"),
+          "fonnapa" => Some("APPH"),
+          "polyton" => Some("PGR "),
+          "arevmda" => Some("HYE "),
+          "provenc" => Some("PRO "),
+          _ => None,
+        })
+    });
+    if let Some(special) = special {
+      return vec![special.to_string()];
+    }
+    // the extended language subtag is more specific, e.g. `yue` of `zh-yue`
+    let Some(language) = tag
+      .extended_languages
+      .first()
+      .or(tag.language.as_ref())
+      .map(String::as_str)
+    else {
+      return Vec::new();
+    };
+    let records = language_tags(language);
+    let is_chinese = records.iter().any(|record| record.tag == "ZHS ");
+    if is_chinese {
+      let traditional = match tag.region.as_deref() {
+        Some("HK") => Some(vec!["ZHH "]),
+        Some("MO") => Some(vec!["ZHTM", "ZHH "]),
+        Some("TW") => Some(vec!["ZHT "]),
+        _ => None,
+      };
+      // simplified Chinese unless the script or region tells otherwise
+      let chinese = match (tag.script.as_deref(), traditional) {
+        (Some("Hant"), None) => vec!["ZHT "],
+        (Some("Hans"), _) | (_, None) => vec!["ZHS "],
+        (_, Some(traditional)) => traditional,
+      };
+      return chinese.into_iter().map(str::to_string).collect();
+    }
+    if records.is_empty() && language.len() == 3 {
+      return vec![padded(&language.to_ascii_uppercase())];
+    }
+    records
+      .into_iter()
+      .map(|record| record.tag.to_string())
+      .collect()
+  }
+}
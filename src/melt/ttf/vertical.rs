@@ -0,0 +1,138 @@
+/// This mod resolves glyphs and metrics for top-to-bottom setting, i.e.
+/// `vrt2`/`vert` substitutions, advances of `vmtx` and origins of `VORG`.
+use crate::melt::repr::FontRepr;
+use crate::melt::ttf::scripts::script_tags;
+use rustybuzz::UnicodeBuffer;
+use serde::{Deserialize, Serialize};
+use ttf_parser::gsub::{SingleSubstitution, SubstitutionSubtable};
+use ttf_parser::opentype_layout::{Feature, LanguageSystem, LayoutTable};
+use ttf_parser::{GlyphId, Tag};
+
+/// The default language system a shaper selects for a script, following
+/// harfbuzz: the first tag of the script present in the table (e.g. `kana`
+/// for `Kana`), otherwise `DFLT`, `dflt` or `latn`.
+fn language_system<'a>(
+  table: &LayoutTable<'a>,
+  script: Option<&str>,
+) -> Option<LanguageSystem<'a>> {
+  let tags = script.map(script_tags).unwrap_or_default();
+  tags
+    .iter()
+    .map(String::as_str)
+    .chain(["DFLT", "dflt", "latn"])
+    .find_map(|tag| {
+      table.scripts.find(Tag::from_bytes_lossy(tag.as_bytes()))
+    })?
+    .default_language
+}
+
+/// The feature with a tag among those of a language system.
+fn find_feature<'a>(
+  table: &LayoutTable<'a>,
+  language_system: &LanguageSystem,
+  tag: Tag,
+) -> Option<Feature<'a>> {
+  language_system
+    .feature_indices
+    .into_iter()
+    .filter_map(|index| table.features.get(index))
+    .find(|feature| feature.tag == tag)
+}
+
+/// Applies the single substitutions of one feature, in lookup order.
+fn substitute(
+  table: &LayoutTable,
+  feature: &Feature,
+  glyph_id: GlyphId,
+) -> GlyphId {
+  let mut glyph_id = glyph_id;
+  for index in feature.lookup_indices {
+    let Some(lookup) = table.lookups.get(index) else {
+      continue;
+    };
+    // the first subtable covering the glyph wins within a lookup
+    let substituted = lookup
+      .subtables
+      .into_iter::<SubstitutionSubtable>()
+      .find_map(|subtable| match subtable {
+        SubstitutionSubtable::Single(SingleSubstitution::Format1 {
+          coverage,
+          delta,
+        }) => coverage
+          .contains(glyph_id)
+          .then(|| GlyphId(glyph_id.0.wrapping_add_signed(delta))),
+        SubstitutionSubtable::Single(SingleSubstitution::Format2 {
+          coverage,
+          substitutes,
+        }) => substitutes.get(coverage.get(glyph_id)?),
+        _ => None,
+      });
+    if let Some(substituted) = substituted {
+      glyph_id = substituted;
+    }
+  }
+  glyph_id
+}
+
+/// The glyph to be set in vertical text, e.g. rotated brackets or
+/// repositioned small kana. `vrt2` supersedes `vert` if both are present.
+/// Like harfbuzz, features are looked up in the language system selected
+/// for the script of the character, then in the whole feature list.
+pub(crate) fn vertical_glyph(
+  repr: &FontRepr,
+  ch: char,
+  glyph_id: GlyphId,
+) -> GlyphId {
+  let Some(gsub) = repr.ttf_parser.tables().gsub else {
+    return glyph_id;
+  };
+  // the script a shaper guesses for the character, e.g. `Hira`
+  let mut buffer = UnicodeBuffer::new();
+  buffer.add(ch, 0);
+  buffer.guess_segment_properties();
+  let script = buffer.script().tag().to_string();
+  let language_system = language_system(&gsub, Some(&script));
+  let tags = [Tag::from_bytes(b"vrt2"), Tag::from_bytes(b"vert")];
+  let feature = tags
+    .iter()
+    .find_map(|&tag| find_feature(&gsub, language_system.as_ref()?, tag))
+    .or_else(|| tags.iter().find_map(|&tag| gsub.features.find(tag)));
+  match feature {
+    Some(feature) => substitute(&gsub, &feature, glyph_id),
+    None => glyph_id,
+  }
+}
+
+/// Metrics of a glyph in top-to-bottom setting, in font units with y axis
+/// pointing up. Fallbacks follow harfbuzz for fonts without vertical
+/// tables.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct VerticalMetrics {
+  // from `vmtx`, one em if absent
+  pub(crate) advance: f32,
+  // height of the vertical origin, at the `VORG` origin, the top of the
+  // bbox plus the top side bearing of `vmtx` or the ascender, in this
+  // order, horizontally it is at half of the horizontal advance
+  pub(crate) y_origin: f32,
+}
+
+impl VerticalMetrics {
+  pub(crate) fn from_glyph_id(repr: &FontRepr, glyph_id: GlyphId) -> Self {
+    let ttf = &repr.ttf_parser;
+    let advance = ttf
+      .glyph_ver_advance(glyph_id)
+      .unwrap_or_else(|| ttf.units_per_em());
+    let top_side_bearing = ttf.glyph_ver_side_bearing(glyph_id);
+    let y_origin = ttf.glyph_y_origin(glyph_id).map_or_else(
+      || match (top_side_bearing, ttf.glyph_bounding_box(glyph_id)) {
+        (Some(tsb), Some(rect)) => f32::from(rect.y_max) + f32::from(tsb),
+        _ => f32::from(ttf.ascender()),
+      },
+      f32::from,
+    );
+    VerticalMetrics {
+      advance: f32::from(advance),
+      y_origin,
+    }
+  }
+}
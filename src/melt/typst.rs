@@ -1,6 +1,8 @@
 /// This module provides Typst compatible font information.
 use super::repr::FontRepr;
+use super::select::FieldSelector;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use typst_library::text::{self, Coverage, FontFlags, FontVariant};
 
 #[allow(clippy::struct_excessive_bools)]
@@ -8,7 +10,9 @@ use typst_library::text::{self, Coverage, FontFlags, FontVariant};
 pub(crate) struct TypstFontInfo {
   family: String,
   variant: FontVariant,
-  coverage: Coverage,
+  // the full Unicode coverage, only computed if selected
+  #[serde(skip_serializing_if = "Option::is_none")]
+  coverage: Option<Coverage>,
 
   is_monospace: bool,
   is_serif: bool,
@@ -17,19 +21,27 @@ pub(crate) struct TypstFontInfo {
 }
 
 impl TypstFontInfo {
-  pub(crate) fn from_repr(repr: &FontRepr) -> Self {
-    let info = &repr.info;
+  pub(crate) fn from_repr(
+    repr: &FontRepr,
+    selector: &FieldSelector,
+  ) -> Option<Self> {
+    let (info, coverage) = if selector.wants("typst.info.coverage") {
+      let info = repr.info()?;
+      (Cow::Borrowed(info), Some(info.coverage.clone()))
+    } else {
+      (repr.info_without_coverage()?, None)
+    };
     let contains_flag = |flag| info.flags.contains(flag);
 
-    Self {
+    Some(Self {
       family: info.family.clone(),
       variant: info.variant,
-      coverage: info.coverage.clone(),
+      coverage,
       is_monospace: contains_flag(FontFlags::MONOSPACE),
       is_serif: contains_flag(FontFlags::SERIF),
       is_variable: contains_flag(FontFlags::VARIABLE),
       has_math_table: contains_flag(FontFlags::MATH),
-    }
+    })
   }
 }
 
@@ -130,15 +142,25 @@ impl TypstFontMetrics {
 }
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct TypstFontIntrospection {
-  info: TypstFontInfo,
-  metrics: TypstFontMetrics,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  info: Option<TypstFontInfo>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  metrics: Option<TypstFontMetrics>,
 }
 
 impl TypstFontIntrospection {
-  pub(crate) fn from_repr(repr: &FontRepr) -> Self {
-    TypstFontIntrospection {
-      info: TypstFontInfo::from_repr(repr),
-      metrics: TypstFontMetrics::from_repr(repr),
-    }
+  pub(crate) fn from_repr(
+    repr: &FontRepr,
+    selector: &FieldSelector,
+  ) -> Option<Self> {
+    selector.wants("typst").then(|| TypstFontIntrospection {
+      info: selector
+        .wants("typst.info")
+        .then(|| TypstFontInfo::from_repr(repr, selector))
+        .flatten(),
+      metrics: selector
+        .wants("typst.metrics")
+        .then(|| TypstFontMetrics::from_repr(repr)),
+    })
   }
 }
@@ -0,0 +1,182 @@
+/// This mod answers questions about characters from a compact copy of the
+/// Unicode Character Database, e.g. that `U+00E9` is `LATIN SMALL LETTER E
+/// WITH ACUTE`, a lowercase letter of the `Latin` script. The tables in
+/// `ucd/` are generated by `scripts/ucd.py`.
+/// - see: <https://www.unicode.org/reports/tr44/>
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+/// `(start, end, value)` ranges of code points, in order.
+type Ranges = Vec<(u32, u32, &'static str)>;
+
+fn ranges(data: &'static str) -> Ranges {
+  data
+    .lines()
+    .filter(|line| !line.starts_with('#'))
+    .filter_map(|line| {
+      let mut fields = line.splitn(3, ';');
+      let start = u32::from_str_radix(fields.next()?, 16).ok()?;
+      let end = u32::from_str_radix(fields.next()?, 16).ok()?;
+      Some((start, end, fields.next()?))
+    })
+    .collect()
+}
+
+fn lookup(ranges: &Ranges, code: u32) -> Option<&'static str> {
+  let index = ranges.partition_point(|&(_, end, _)| end < code);
+  ranges
+    .get(index)
+    .filter(|&&(start, _, _)| start <= code)
+    .map(|&(_, _, value)| value)
+}
+
+static NAMES: LazyLock<Vec<(u32, String)>> = LazyLock::new(|| {
+  let mut names: Vec<(u32, String)> = Vec::new();
+  let mut code = 0;
+  for line in include_str!("ucd/names.txt")
+    .lines()
+    .filter(|line| !line.starts_with('#'))
+  {
+    let mut fields = line.splitn(3, ';');
+    let (Some(delta), Some(shared), Some(suffix)) =
+      (fields.next(), fields.next(), fields.next())
+    else {
+      continue;
+    };
+    let (Ok(delta), Ok(shared)) =
+      (u32::from_str_radix(delta, 16), shared.parse::<usize>())
+    else {
+      continue;
+    };
+    code += delta;
+    let previous = names.last().map_or("", |(_, name)| name.as_str());
+    let name =
+      format!("{}{suffix}", previous.get(..shared).unwrap_or_default());
+    names.push((code, name));
+  }
+  names
+});
+
+/// The version of the Unicode Character Database the tables are generated
+/// from, e.g. `17.0.0`, as their headers record it.
+static UNICODE_VERSION: LazyLock<&'static str> = LazyLock::new(|| {
+  include_str!("ucd/names.txt")
+    .lines()
+    .find_map(|line| {
+      line
+        .strip_prefix("# Generated by scripts/ucd.py from the Unicode Character Database ")?
+        .strip_suffix('.')
+    })
+    .unwrap_or_default()
+});
+static DERIVED_NAMES: LazyLock<Ranges> =
+  LazyLock::new(|| ranges(include_str!("ucd/derived_names.txt")));
+static GENERAL_CATEGORY: LazyLock<Ranges> =
+  LazyLock::new(|| ranges(include_str!("ucd/general_category.txt")));
+static COMBINING_CLASS: LazyLock<Ranges> =
+  LazyLock::new(|| ranges(include_str!("ucd/combining_class.txt")));
+static BIDI_CLASS: LazyLock<Ranges> =
+  LazyLock::new(|| ranges(include_str!("ucd/bidi_class.txt")));
+static SCRIPTS: LazyLock<Ranges> =
+  LazyLock::new(|| ranges(include_str!("ucd/scripts.txt")));
+static SCRIPT_EXTENSIONS: LazyLock<Ranges> =
+  LazyLock::new(|| ranges(include_str!("ucd/script_extensions.txt")));
+static BLOCKS: LazyLock<Ranges> =
+  LazyLock::new(|| ranges(include_str!("ucd/blocks.txt")));
+static EAST_ASIAN_WIDTH: LazyLock<Ranges> =
+  LazyLock::new(|| ranges(include_str!("ucd/east_asian_width.txt")));
+
+/// The name of a precomposed Hangul syllable, e.g. `GAG` for `U+AC01`.
+/// - see: The Unicode Standard, section 3.12
+fn hangul_syllable(code: u32) -> Option<String> {
+  const LEADING: [&str; 19] = [
+    "G", "GG", "N", "D", "DD", "R", "M", "B", "BB", "S", "SS", "", "J", "JJ",
+    "C", "K", "T", "P", "H",
+  ];
+  const VOWEL: [&str; 21] = [
+    "A", "AE", "YA", "YAE", "EO", "E", "YEO", "YE", "O", "WA", "WAE", "OE",
+    "YO", "U", "WEO", "WE", "WI", "YU", "EU", "YI", "I",
+  ];
+  const TRAILING: [&str; 28] = [
+    "", "G", "GG", "GS", "N", "NJ", "NH", "D", "L", "LG", "LM", "LB", "LS",
+    "LT", "LP", "LH", "M", "B", "BS", "S", "SS", "NG", "J", "C", "K", "T",
+    "P", "H",
+  ];
+  let index = usize::try_from(code.checked_sub(0xAC00)?).ok()?;
+  Some(format!(
+    "{}{}{}",
+    LEADING.get(index / (21 * 28))?,
+    VOWEL[index % (21 * 28) / 28],
+    TRAILING[index % 28]
+  ))
+}
+
+fn name(code: u32) -> Option<String> {
+  if let Some(prefix) = lookup(&DERIVED_NAMES, code) {
+    return if prefix == "HANGUL SYLLABLE" {
+      Some(format!("{prefix} {}", hangul_syllable(code)?))
+    } else {
+      Some(format!("{prefix}-{code:X}"))
+    };
+  }
+  let index = NAMES.binary_search_by_key(&code, |&(code, _)| code).ok()?;
+  Some(NAMES[index].1.clone())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct CharProperties {
+  codepoint: u32,
+  // `None` for unassigned code points and those without a name, e.g.
+  // controls, surrogates and private use characters
+  name: Option<String>,
+  // short alias, e.g. `Lu`, `Cn` if unassigned
+  general_category: String,
+  // long alias, e.g. `Latin`, `Unknown` if unassigned
+  script: String,
+  // scripts the character is used with, `[script]` for most characters
+  script_extensions: Vec<String>,
+  // e.g. `Basic Latin`, `None` outside of blocks
+  block: Option<String>,
+  // short alias, e.g. `L` or `AL`, `None` if unassigned
+  bidi_class: Option<String>,
+  // canonical combining class, e.g. `230` for marks above
+  combining_class: u8,
+  // short alias, e.g. `W` or `Na`
+  east_asian_width: String,
+  assigned: bool,
+  // of the embedded tables, e.g. `17.0.0`, characters added later are
+  // unassigned
+  unicode_version: String,
+}
+
+impl CharProperties {
+  /// `None` if the code point is beyond `U+10FFFF`.
+  pub(crate) fn from_codepoint(code: u32) -> Option<Self> {
+    if code > 0x10_FFFF {
+      return None;
+    }
+    let general_category = lookup(&GENERAL_CATEGORY, code).unwrap_or("Cn");
+    let script = lookup(&SCRIPTS, code).unwrap_or("Unknown");
+    Some(CharProperties {
+      codepoint: code,
+      name: name(code),
+      general_category: general_category.to_string(),
+      script: script.to_string(),
+      script_extensions: lookup(&SCRIPT_EXTENSIONS, code)
+        .unwrap_or(script)
+        .split(' ')
+        .map(str::to_string)
+        .collect(),
+      block: lookup(&BLOCKS, code).map(str::to_string),
+      bidi_class: lookup(&BIDI_CLASS, code).map(str::to_string),
+      combining_class: lookup(&COMBINING_CLASS, code)
+        .and_then(|class| class.parse().ok())
+        .unwrap_or(0),
+      east_asian_width: lookup(&EAST_ASIAN_WIDTH, code)
+        .unwrap_or("N")
+        .to_string(),
+      assigned: general_category != "Cn",
+      unicode_version: UNICODE_VERSION.to_string(),
+    })
+  }
+}
@@ -1,8 +1,8 @@
 #let melt = plugin("./melt.wasm")
 #import "@preview/oxifmt:1.0.0": strfmt
 
-// NOTE: for accurately checking if the codepoint is an _assigned_ Unicode codepoint
-// we need to query the Unicode database, which is not yet implemented
+// NOTE: whether the codepoint is _assigned_ is told by `char-properties`, it is not
+// checked here since fonts may cover characters newer than the embedded Unicode database
 #let _is-valid-unicode(codepoint) = {
   (
     type(codepoint) == int
@@ -12,21 +12,45 @@
   )
 }
 
+#let _into_fields(fields) = {
+  if fields == auto {
+    none
+  } else {
+    assert(
+      type(fields) == array and fields.all(it => type(it) == str),
+      message: "`fields` must be `auto` or an array of strings.",
+    )
+    fields
+  }
+}
+
 /// Returns an array of information of fonts in collection.
 /// If it's not a font collection, it will be in length 1.
 /// If some fatal parsing errors happened internal, it will be `()`
 ///
 /// - data (bytes): font data
+/// - fields (auto, array): dot separated paths of fields to be computed, e.g. `("typst.info.family", "metrics")`, all fields if `auto`
+/// - instances (bool): whether to return one dictionary per named instance of variable fonts, see its `instance` field
 /// -> array
-#let fonts-collection-info(data) = cbor(melt.fonts_collection_info(data))
+#let fonts-collection-info(data, fields: auto, instances: false) = {
+  assert(type(instances) == bool, message: "`instances` must be a bool.")
+  cbor(melt.fonts_collection_info(
+    data,
+    cbor.encode((fields: _into_fields(fields), instances: instances)),
+  ))
+}
 
 /// Return a dictionary of information of a font.
 /// If some fatal parsing errors happened internal, it will be `()`
 ///
 /// - data (bytes): font data
 /// - index (int): index of the font in collection, if not a collection, it shall be 0.
+/// - fields (auto, array): dot separated paths of fields to be computed, all fields if `auto`
 /// -> dictionary
-#let font-info(data, index: 0) = fonts-collection-info(data).at(index)
+#let font-info(data, index: 0, fields: auto) = fonts-collection-info(
+  data,
+  fields: fields,
+).at(index)
 
 /// Return a bool indicating if the font contains the given codepoint.
 /// Notice that we'll not check whether provided `parsed-data` is valid.
@@ -53,6 +77,40 @@
   false
 }
 
+/// Return the best name of a font for each name id, e.g. the family name to
+/// show in a font menu, with the record it is read from.
+/// If some fatal parsing errors happened internal, it will be `none`
+///
+/// - data (bytes): font data
+/// - index (int): index of the font in collection, if not a collection, it shall be 0.
+/// - languages (array): BCP 47 language tags in order of preference, e.g. `("zh-Hant", "ja")`, `"en-US"` is tried after them
+/// - strategy (str): how family and subfamily names are chosen, `"typographic"`, `"wws"`, `"legacy"` or `"typst"`
+/// -> dictionary
+#let resolve-names(
+  data,
+  index: 0,
+  languages: (),
+  strategy: "typographic",
+) = {
+  assert(
+    type(index) == int and 0 <= index and index < 0xFFFFFFFF,
+    message: "index must be an integer between 0 and 2^32 - 1",
+  )
+  assert(
+    type(languages) == array and languages.all(it => type(it) == str),
+    message: "`languages` must be an array of strings.",
+  )
+  assert(
+    strategy in ("typographic", "wws", "legacy", "typst"),
+    message: "`strategy` must be one of \"typographic\", \"wws\", \"legacy\" or \"typst\".",
+  )
+  cbor(melt.resolve_names(
+    data,
+    cbor.encode(index),
+    cbor.encode((languages: languages, strategy: strategy)),
+  ))
+}
+
 /// Return an array of glyph information for the given codepoints.
 ///
 /// - data (bytes): font data
@@ -71,6 +129,46 @@
   cbor(melt.glyphs_infos(data, cbor.encode(index), cbor.encode(codepoints)))
 }
 
+/// Parse glyph names into codepoints following the Adobe Glyph List
+/// Specification, e.g. `"f_f_i"`, `"a.sc"` or `"uni0041.alt"`.
+///
+/// - names (array): array of glyph names
+/// -> array
+#let parse-glyph-names(names) = {
+  assert(
+    type(names) == array and names.all(it => type(it) == str),
+    message: "names must be an array of strings.",
+  )
+  cbor(melt.parse_glyph_names(cbor.encode(names)))
+}
+
+/// Return the OpenType script and language system tags a shaper selects for
+/// BCP 47 language tags, e.g. `("hani",)` and `("ZHH ",)` for `zh-Hant-HK`.
+///
+/// - languages (array): array of BCP 47 language tags
+/// -> array
+#let opentype-tags(languages) = {
+  assert(
+    type(languages) == array and languages.all(it => type(it) == str),
+    message: "languages must be an array of strings.",
+  )
+  cbor(melt.opentype_tags(cbor.encode(languages)))
+}
+
+/// Return properties of characters from the Unicode Character Database,
+/// e.g. name, general category, script and block.
+///
+/// - codepoints (array): array of codepoints, surrogates included
+/// -> array
+#let char-properties(codepoints) = {
+  assert(
+    type(codepoints) == array
+      and codepoints.all(it => type(it) == int and 0 <= it and it <= 0x10FFFF),
+    message: "codepoints must be an array of Unicode codepoints.",
+  )
+  cbor(melt.char_properties(cbor.encode(codepoints)))
+}
+
 #let _into_css_color(c) = {
   if type(c) == str {
     c
@@ -155,15 +253,8 @@
   )
 }
 
-/// Return an array of glyph shapes for the given codepoints.
-///
-/// - data (bytes): font data
-/// - index (int): index of the font in the collection
-/// - codepoints (array): array of valid Unicode codepoints
-/// - styles (auto, dictionary, function): styles to be applied to SVG template
-/// -> array
-#let glyphs-shapes(data, index, codepoints, styles: auto) = {
-  let styles-applier = if styles == auto {
+#let _styles-applier(styles) = {
+  if styles == auto {
     let styles = svg-path-styles()
     let scale = styles.scale
     (template, metrics) => strfmt(
@@ -198,6 +289,98 @@
       repr(type(styles)),
     ))
   }
+}
+
+#let _into_synthesis(embolden, oblique, extend) = {
+  assert(
+    type(embolden) in (int, float),
+    message: "`embolden` must be a number in font units.",
+  )
+  assert(type(oblique) == angle, message: "`oblique` must be an angle.")
+  assert(
+    type(extend) in (int, float, ratio) and float(extend) > 0,
+    message: "`extend` must be a positive number or ratio.",
+  )
+  (
+    embolden: float(embolden),
+    oblique: oblique.deg(),
+    extend: float(extend),
+  )
+}
+
+#let _hinting-engines = ("auto", "interpreter", "autohinter")
+#let _hinting-targets = ("mono", "normal", "light", "lcd", "vertical-lcd")
+
+#let _into_hinting(ppem, engine, target, preserve-linear-metrics) = {
+  assert(
+    type(ppem) in (int, float) and ppem > 0,
+    message: "`ppem` must be a positive number.",
+  )
+  assert(
+    engine in _hinting-engines,
+    message: "`engine` must be one of " + _hinting-engines.join(", ") + ".",
+  )
+  assert(
+    target in _hinting-targets,
+    message: "`target` must be one of " + _hinting-targets.join(", ") + ".",
+  )
+  assert(
+    type(preserve-linear-metrics) == bool,
+    message: "`preserve-linear-metrics` must be a bool.",
+  )
+  (
+    ppem: float(ppem),
+    engine: engine,
+    target: target,
+    preserve_linear_metrics: preserve-linear-metrics,
+  )
+}
+
+// `none` or a dictionary with keys of `glyphs-hinted-outlines`
+#let _into_shape_hinting(hinting) = {
+  if hinting == none {
+    return none
+  }
+  assert(
+    type(hinting) == dictionary,
+    message: "`hinting` must be none or a dictionary.",
+  )
+  _into_hinting(
+    hinting.at("ppem", default: 16),
+    hinting.at("engine", default: "auto"),
+    hinting.at("target", default: "normal"),
+    hinting.at("preserve-linear-metrics", default: false),
+  )
+}
+
+/// Return an array of glyph shapes for the given codepoints.
+///
+/// - data (bytes): font data
+/// - index (int): index of the font in the collection
+/// - codepoints (array): array of valid Unicode codepoints
+/// - styles (auto, dictionary, function): styles to be applied to SVG template
+/// - embolden (int, float): synthetic emboldening in font units, stems get thicker by this amount and so does the advance
+/// - oblique (angle): synthetic slant, positive leans to the right
+/// - extend (int, float, ratio): synthetic horizontal scale, less than 1 condenses and greater than 1 extends
+/// - hinting (none, dictionary): hint outlines at a pixel size before scaling them back to font units, keys are `ppem`, `engine`, `target` and `preserve-linear-metrics` of `glyphs-hinted-outlines`
+/// - vertical (bool): set glyphs top to bottom, with `vrt2`/`vert` substitutions applied and the viewBox spanning the vertical advance below the vertical origin
+/// - anchors (bool): draw `GPOS` attachment anchors as rings, marks in red, bases in blue and cursive entries and exits in green
+/// -> array
+#let glyphs-shapes(
+  data,
+  index,
+  codepoints,
+  styles: auto,
+  embolden: 0,
+  oblique: 0deg,
+  extend: 1.0,
+  hinting: none,
+  vertical: false,
+  anchors: false,
+) = {
+  let styles-applier = _styles-applier(styles)
+  assert(type(vertical) == bool, message: "`vertical` must be a bool.")
+  assert(type(anchors) == bool, message: "`anchors` must be a bool.")
   assert(
     type(index) == int and 0 <= index and index < 0xFFFFFFFF,
     message: "index must be an integer between 0 and 2^32 - 1",
@@ -210,9 +393,661 @@
     data,
     cbor.encode(index),
     cbor.encode(codepoints),
+    cbor.encode((
+      synthesis: _into_synthesis(embolden, oblique, extend),
+      hinting: _into_shape_hinting(hinting),
+      vertical: vertical,
+      anchors: anchors,
+    )),
   )).map(
     shape => {
       styles-applier(shape.template, shape.metrics)
     },
   )
 }
+
+/// Return which font of an ordered font list renders each grapheme cluster of
+/// `text`, following the same rules Typst applies to its `font` family list.
+///
+/// - text (str): text to be resolved
+/// - fonts (array): array of font data (bytes), or pairs of font data and index in the collection
+/// -> dictionary
+#let fonts-fallback(text, fonts) = {
+  assert(type(text) == str, message: "`text` must be a string.")
+  assert(
+    type(fonts) == array,
+    message: "`fonts` must be an array of font data or (data, index) pairs.",
+  )
+  let fonts = fonts.map(font => if type(font) == bytes {
+    (font, 0)
+  } else {
+    assert(
+      type(font) == array
+        and font.len() == 2
+        and type(font.at(0)) == bytes
+        and type(font.at(1)) == int,
+      message: "each font must be `bytes` or a `(bytes, int)` pair.",
+    )
+    font
+  })
+  cbor(melt.fonts_fallback(cbor.encode(text), cbor.encode(fonts)))
+}
+
+#let _into_shaping_options(
+  size,
+  shaping,
+  kerning,
+  features,
+  direction,
+  script,
+  lang,
+) = {
+  assert(
+    type(size) == length and size.em == 0,
+    message: "`size` must be an absolute length.",
+  )
+  let features = if type(features) == dictionary {
+    features.pairs().map(((tag, value)) => tag + "=" + str(int(value)))
+  } else if type(features) == array {
+    features
+  } else {
+    panic(strfmt(
+      "type of `features` shall be `array` or `dictionary`, find {}",
+      repr(type(features)),
+    ))
+  }
+  (
+    size: size / 1pt,
+    shaping: shaping,
+    kerning: kerning,
+    features: features,
+    direction: if direction == auto { none } else { repr(direction) },
+    script: if script == auto { none } else { script },
+    language: if lang == auto { none } else { lang },
+  )
+}
+
+/// Return the total advance, ink bounding box and per-cluster advances of
+/// `text` set in the given font, all quantities are in pt.
+///
+/// - data (bytes): font data
+/// - text (str): text to be measured
+/// - index (int): index of the font in the collection
+/// - size (length): font size, must be absolute
+/// - shaping (bool): whether to apply OpenType shaping, otherwise characters are mapped one by one
+/// - kerning (bool): whether to apply kerning
+/// - features (array, dictionary): OpenType features, either `("liga=0", "+ss01")` or `(liga: 0, ss01: 1)`
+/// - direction (auto, direction): text direction, guessed from `text` if `auto`
+/// - script (auto, str): ISO 15924 script tag, guessed from `text` if `auto`
+/// - lang (auto, str): BCP-47 language tag
+/// -> dictionary
+#let measure-text(
+  data,
+  text,
+  index: 0,
+  size: 11pt,
+  shaping: true,
+  kerning: true,
+  features: (),
+  direction: auto,
+  script: auto,
+  lang: auto,
+) = {
+  assert(
+    type(index) == int and 0 <= index and index < 0xFFFFFFFF,
+    message: "index must be an integer between 0 and 2^32 - 1",
+  )
+  assert(type(text) == str, message: "`text` must be a string.")
+  cbor(melt.measure_text(
+    data,
+    cbor.encode(index),
+    cbor.encode(text),
+    cbor.encode(_into_shaping_options(
+      size,
+      shaping,
+      kerning,
+      features,
+      direction,
+      script,
+      lang,
+    )),
+  ))
+}
+
+/// Parse a font once and keep it inside the plugin, so that later
+/// `*-by-handle` calls do not parse it again. Returns a dictionary with the
+/// new `plugin` state and the `handle` of the font, pass it to the
+/// `*-by-handle` functions.
+///
+/// The plugin keeps at most 8 fonts, the least recently used one is evicted
+/// first, calls on an evicted font return `none`.
+///
+/// - data (bytes): font data
+/// - index (int): index of the font in the collection
+/// - loaded (none, dictionary): a font returned by a previous `load-font`, to keep the fonts loaded by it
+/// -> dictionary
+#let load-font(data, index: 0, loaded: none) = {
+  assert(
+    type(index) == int and 0 <= index and index < 0xFFFFFFFF,
+    message: "index must be an integer between 0 and 2^32 - 1",
+  )
+  let base = if loaded == none { melt } else { loaded.plugin }
+  let state = plugin.transition(base.load_font, data, cbor.encode(index))
+  let handle = cbor(state.last_loaded_font())
+  assert(handle != none, message: "failed to parse the font.")
+  (plugin: state, handle: handle)
+}
+
+/// Same as `glyphs-info`, but on a font returned by `load-font`.
+///
+/// - font (dictionary): a font returned by `load-font`
+/// - codepoints (array): array of valid Unicode codepoints
+/// -> array, none
+#let glyphs-info-by-handle(font, codepoints) = {
+  assert(
+    type(codepoints) == array and codepoints.all(_is-valid-unicode),
+    message: "codepoints must be an array of valid Unicode codepoints.",
+  )
+  cbor(font.plugin.glyphs_infos_by_handle(
+    cbor.encode(font.handle),
+    cbor.encode(codepoints),
+  ))
+}
+
+/// Same as `glyphs-shapes`, but on a font returned by `load-font`.
+///
+/// - font (dictionary): a font returned by `load-font`
+/// - codepoints (array): array of valid Unicode codepoints
+/// - styles (auto, dictionary, function): styles to be applied to SVG template
+/// - embolden (int, float): synthetic emboldening in font units
+/// - oblique (angle): synthetic slant
+/// - extend (int, float, ratio): synthetic horizontal scale
+/// - hinting (none, dictionary): see `glyphs-shapes`
+/// - vertical (bool): see `glyphs-shapes`
+/// - anchors (bool): see `glyphs-shapes`
+/// -> array, none
+#let glyphs-shapes-by-handle(
+  font,
+  codepoints,
+  styles: auto,
+  embolden: 0,
+  oblique: 0deg,
+  extend: 1.0,
+  hinting: none,
+  vertical: false,
+  anchors: false,
+) = {
+  let styles-applier = _styles-applier(styles)
+  assert(type(vertical) == bool, message: "`vertical` must be a bool.")
+  assert(type(anchors) == bool, message: "`anchors` must be a bool.")
+  assert(
+    type(codepoints) == array and codepoints.all(_is-valid-unicode),
+    message: "codepoints must be an array of valid Unicode codepoints.",
+  )
+  let shapes = cbor(font.plugin.glyphs_shapes_by_handle(
+    cbor.encode(font.handle),
+    cbor.encode(codepoints),
+    cbor.encode((
+      synthesis: _into_synthesis(embolden, oblique, extend),
+      hinting: _into_shape_hinting(hinting),
+      vertical: vertical,
+      anchors: anchors,
+    )),
+  ))
+  if shapes == none {
+    return none
+  }
+  shapes.map(shape => styles-applier(shape.template, shape.metrics))
+}
+
+/// Return an array of glyph outlines for the given codepoints, as structured
+/// path commands in font units (y axis pointing up).
+///
+/// - data (bytes): font data
+/// - index (int): index of the font in the collection
+/// - codepoints (array): array of valid Unicode codepoints
+/// - cubic (bool): whether to convert quadratic Béziers to cubic ones
+/// - embolden (int, float): synthetic emboldening in font units
+/// - oblique (angle): synthetic slant
+/// - extend (int, float, ratio): synthetic horizontal scale
+/// -> array
+#let glyphs-outlines(
+  data,
+  index,
+  codepoints,
+  cubic: false,
+  embolden: 0,
+  oblique: 0deg,
+  extend: 1.0,
+) = {
+  assert(
+    type(index) == int and 0 <= index and index < 0xFFFFFFFF,
+    message: "index must be an integer between 0 and 2^32 - 1",
+  )
+  assert(
+    type(codepoints) == array and codepoints.all(_is-valid-unicode),
+    message: "codepoints must be an array of valid Unicode codepoints.",
+  )
+  assert(type(cubic) == bool, message: "`cubic` must be a bool.")
+  cbor(melt.glyphs_outlines(
+    data,
+    cbor.encode(index),
+    cbor.encode(codepoints),
+    cbor.encode((
+      cubic: cubic,
+      synthesis: _into_synthesis(embolden, oblique, extend),
+    )),
+  ))
+}
+
+/// Return an array of hinted glyph outlines for the given codepoints, as
+/// structured path commands in pixels (y axis pointing up).
+///
+/// - data (bytes): font data
+/// - index (int): index of the font in the collection
+/// - codepoints (array): array of valid Unicode codepoints
+/// - ppem (int, float): pixels per em
+/// - engine (str): `"auto"`, `"interpreter"` (TrueType bytecode) or `"autohinter"`
+/// - target (str): `"mono"`, `"normal"`, `"light"`, `"lcd"` or `"vertical-lcd"`
+/// - preserve-linear-metrics (bool): keep unhinted advances
+/// - cubic (bool): whether to convert quadratic Béziers to cubic ones
+/// -> array, none
+#let glyphs-hinted-outlines(
+  data,
+  index,
+  codepoints,
+  ppem: 16,
+  engine: "auto",
+  target: "normal",
+  preserve-linear-metrics: false,
+  cubic: false,
+) = {
+  assert(
+    type(index) == int and 0 <= index and index < 0xFFFFFFFF,
+    message: "index must be an integer between 0 and 2^32 - 1",
+  )
+  assert(
+    type(codepoints) == array and codepoints.all(_is-valid-unicode),
+    message: "codepoints must be an array of valid Unicode codepoints.",
+  )
+  assert(type(cubic) == bool, message: "`cubic` must be a bool.")
+  cbor(melt.glyphs_hinted_outlines(
+    data,
+    cbor.encode(index),
+    cbor.encode(codepoints),
+    cbor.encode((
+      cubic: cubic,
+      hinting: _into_hinting(ppem, engine, target, preserve-linear-metrics),
+    )),
+  ))
+}
+
+/// Return the `GPOS` attachment anchors of glyphs for the given codepoints,
+/// from mark-to-base, mark-to-ligature, mark-to-mark and cursive lookups.
+///
+/// - data (bytes): font data
+/// - index (int): index of the font in the collection
+/// - codepoints (array): array of valid Unicode codepoints
+/// -> array, none
+#let glyphs-anchors(data, index, codepoints) = {
+  assert(
+    type(index) == int and 0 <= index and index < 0xFFFFFFFF,
+    message: "index must be an integer between 0 and 2^32 - 1",
+  )
+  assert(
+    type(codepoints) == array and codepoints.all(_is-valid-unicode),
+    message: "codepoints must be an array of valid Unicode codepoints.",
+  )
+  cbor(melt.glyphs_anchors(
+    data,
+    cbor.encode(index),
+    cbor.encode(codepoints),
+  ))
+}
+
+/// Return raw `glyf` data of glyphs for the given codepoints: control
+/// points with on-curve flags, contour end indices, instruction length,
+/// overlap flag and component references of composite glyphs. Returns
+/// `none` if the font has no `glyf` table (e.g. CFF fonts).
+///
+/// - data (bytes): font data
+/// - index (int): index of the font in the collection
+/// - codepoints (array): array of valid Unicode codepoints
+/// -> array, none
+#let glyphs-glyf(data, index, codepoints) = {
+  assert(
+    type(index) == int and 0 <= index and index < 0xFFFFFFFF,
+    message: "index must be an integer between 0 and 2^32 - 1",
+  )
+  assert(
+    type(codepoints) == array and codepoints.all(_is-valid-unicode),
+    message: "codepoints must be an array of valid Unicode codepoints.",
+  )
+  cbor(melt.glyphs_glyf(
+    data,
+    cbor.encode(index),
+    cbor.encode(codepoints),
+  ))
+}
+
+/// Convert contours from `glyphs-outlines` into Typst's native `curve`
+/// components, y axis is flipped so that the baseline is at `y = 0`.
+///
+/// - contours (array): contours of a glyph outline
+/// - scale (length): length of one font unit, e.g. `12pt / units-per-em`
+/// -> array
+#let outline-components(contours, scale: 0.01pt) = {
+  let point(p) = (p.at(0) * scale, -p.at(1) * scale)
+  contours
+    .flatten()
+    .map(command => {
+      let points = command.points.map(point)
+      if command.op == "move" {
+        curve.move(..points)
+      } else if command.op == "line" {
+        curve.line(..points)
+      } else if command.op == "quad" {
+        curve.quad(..points)
+      } else if command.op == "cubic" {
+        curve.cubic(..points)
+      } else {
+        curve.close()
+      }
+    })
+}
+
+#let _raster-formats = ("gray", "rgba")
+
+#let _into_rgba8(color) = {
+  assert(type(color) == std.color, message: "colors must be of type `color`.")
+  color.rgb().components().map(c => int(calc.round(c / 100% * 255)))
+}
+
+#let _into_raster_options(ppem, offset, format, fill, background, hinting) = {
+  assert(
+    type(ppem) in (int, float) and ppem > 0,
+    message: "`ppem` must be a positive number.",
+  )
+  assert(
+    type(offset) == array
+      and offset.len() == 2
+      and offset.all(v => type(v) in (int, float)),
+    message: "`offset` must be an array of two numbers in pixels.",
+  )
+  assert(
+    format in _raster-formats,
+    message: "`format` must be one of " + _raster-formats.join(", ") + ".",
+  )
+  (
+    ppem: float(ppem),
+    offset: offset.map(float),
+    format: format,
+    color: _into_rgba8(fill),
+    background: if background == none { none } else {
+      _into_rgba8(background)
+    },
+    hinting: _into_shape_hinting(hinting),
+  )
+}
+
+/// Rasterize glyphs of the given codepoints into anti-aliased PNG bitmaps,
+/// pixels do not depend on the renderer of the viewer.
+///
+/// - data (bytes): font data
+/// - index (int): index of the font in the collection
+/// - codepoints (array): array of valid Unicode codepoints
+/// - ppem (int, float): pixels per em
+/// - offset (array): subpixel offset `(x, y)` of the origin in pixels, y axis points up
+/// - format (str): `"gray"` for coverage (255 is full ink) or `"rgba"` for `fill` over `background`
+/// - fill (color): ink color of `rgba` bitmaps
+/// - background (none, color): background of `rgba` bitmaps, transparent if `none`
+/// - hinting (none, dictionary): see `glyphs-shapes`, `ppem` is ignored
+/// -> array, none
+#let glyphs-rasters(
+  data,
+  index,
+  codepoints,
+  ppem: 16,
+  offset: (0, 0),
+  format: "gray",
+  fill: black,
+  background: none,
+  hinting: none,
+) = {
+  assert(
+    type(index) == int and 0 <= index and index < 0xFFFFFFFF,
+    message: "index must be an integer between 0 and 2^32 - 1",
+  )
+  assert(
+    type(codepoints) == array and codepoints.all(_is-valid-unicode),
+    message: "codepoints must be an array of valid Unicode codepoints.",
+  )
+  cbor(melt.glyphs_rasters(
+    data,
+    cbor.encode(index),
+    cbor.encode(codepoints),
+    cbor.encode(_into_raster_options(
+      ppem,
+      offset,
+      format,
+      fill,
+      background,
+      hinting,
+    )),
+  ))
+}
+
+/// Shape `text` with the given font and rasterize it into one anti-aliased
+/// PNG bitmap.
+///
+/// - data (bytes): font data
+/// - text (str): text to be rendered
+/// - index (int): index of the font in the collection
+/// - ppem (int, float): pixels per em
+/// - offset (array): subpixel offset `(x, y)` of the origin in pixels, y axis points up
+/// - format (str): `"gray"` or `"rgba"`
+/// - fill (color): ink color of `rgba` bitmaps
+/// - background (none, color): background of `rgba` bitmaps, transparent if `none`
+/// - hinting (none, dictionary): see `glyphs-shapes`, `ppem` is ignored
+/// - shaping (bool): whether to apply OpenType shaping
+/// - kerning (bool): whether to apply kerning
+/// - features (array, dictionary): OpenType features, see `measure-text`
+/// - direction (auto, direction): text direction, guessed from `text` if `auto`
+/// - script (auto, str): ISO 15924 script tag, guessed from `text` if `auto`
+/// - lang (auto, str): BCP-47 language tag
+/// -> dictionary, none
+#let text-raster(
+  data,
+  text,
+  index: 0,
+  ppem: 16,
+  offset: (0, 0),
+  format: "gray",
+  fill: black,
+  background: none,
+  hinting: none,
+  shaping: true,
+  kerning: true,
+  features: (),
+  direction: auto,
+  script: auto,
+  lang: auto,
+) = {
+  assert(
+    type(index) == int and 0 <= index and index < 0xFFFFFFFF,
+    message: "index must be an integer between 0 and 2^32 - 1",
+  )
+  assert(type(text) == str, message: "`text` must be a string.")
+  cbor(melt.text_raster(
+    data,
+    cbor.encode(index),
+    cbor.encode(text),
+    cbor.encode((
+      raster: _into_raster_options(
+        ppem,
+        offset,
+        format,
+        fill,
+        background,
+        hinting,
+      ),
+      shaping: _into_shaping_options(
+        11pt,
+        shaping,
+        kerning,
+        features,
+        direction,
+        script,
+        lang,
+      ),
+    )),
+  ))
+}
+
+#let _grid-labels = ("name", "id", "codepoint")
+
+// SVG paint of a Typst color, strings are passed through
+#let _into_svg_paint(paint) = {
+  if type(paint) == color { paint.to-hex() } else {
+    assert(type(paint) == str, message: "paints must be colors or strings.")
+    paint
+  }
+}
+
+/// Render glyphs of a face, or a range of them, into one SVG specimen
+/// sheet. Returns a dictionary with `svg` (str), its `width` and `height`
+/// (in SVG user units), `page`, `pages`, `total` (glyphs in the whole
+/// selection) and `glyphs` (glyph ids on this page), or `none` if the font
+/// cannot be read or the page does not exist.
+///
+/// - data (bytes): font data
+/// - index (int): index of the font in the collection
+/// - glyphs (none, array): inclusive range `(start, end)` of glyph ids, all glyphs if `none`
+/// - codepoints (none, array): inclusive range `(start, end)` of codepoints, unmapped ones are skipped, takes precedence over `glyphs`
+/// - cell (int, float): side length of square cells in SVG user units
+/// - columns (int): number of columns
+/// - labels (array): labels below each glyph, any of `"name"`, `"id"` and `"codepoint"`
+/// - baseline (bool): whether to draw the baseline
+/// - advance (bool): whether to mark the origin and the advance
+/// - per-page (none, int): glyphs per page, all glyphs on one page if `none`
+/// - page (int): zero-based page number
+/// - fill (color, str): paint of glyphs
+/// - guide (color, str): paint of baseline and advance markers
+/// - border (color, str): paint of cell borders
+/// - label (color, str): paint of labels
+/// -> dictionary, none
+#let glyph-grid(
+  data,
+  index: 0,
+  glyphs: none,
+  codepoints: none,
+  cell: 96,
+  columns: 16,
+  labels: _grid-labels,
+  baseline: true,
+  advance: true,
+  per-page: none,
+  page: 0,
+  fill: black,
+  guide: rgb("#d33"),
+  border: rgb("#ccc"),
+  label: rgb("#666"),
+) = {
+  assert(
+    type(index) == int and 0 <= index and index < 0xFFFFFFFF,
+    message: "index must be an integer between 0 and 2^32 - 1",
+  )
+  for range in (glyphs, codepoints) {
+    assert(
+      range == none
+        or (
+          type(range) == array
+            and range.len() == 2
+            and range.all(v => type(v) == int and v >= 0)
+        ),
+      message: "ranges must be none or arrays `(start, end)` of non-negative integers.",
+    )
+  }
+  assert(
+    type(labels) == array and labels.all(l => l in _grid-labels),
+    message: "`labels` must be an array of " + _grid-labels.join(", ") + ".",
+  )
+  assert(
+    per-page == none or (type(per-page) == int and per-page > 0),
+    message: "`per-page` must be none or a positive integer.",
+  )
+  cbor(melt.glyph_grid(
+    data,
+    cbor.encode(index),
+    cbor.encode((
+      glyphs: glyphs,
+      codepoints: codepoints,
+      cell: float(cell),
+      columns: columns,
+      labels: labels,
+      baseline: baseline,
+      advance: advance,
+      per_page: per-page,
+      page: page,
+      fill: _into_svg_paint(fill),
+      guide: _into_svg_paint(guide),
+      border: _into_svg_paint(border),
+      label: _into_svg_paint(label),
+    )),
+  ))
+}
+
+/// Shape `text` with the given font and render it into one SVG document,
+/// each glyph outline is defined once and placed with `<use>`. Returns a
+/// dictionary with `svg` (str), the viewport `x_origin`, `y_origin`,
+/// `width` and `height`, `advance`, `ascent`, `descent` and the ink `bbox`,
+/// all in pt, or `none` if the font cannot be read.
+///
+/// - data (bytes): font data
+/// - text (str): text to be rendered
+/// - index (int): index of the font in the collection
+/// - size (length): font size, must be absolute
+/// - fill (color, str): paint of glyphs
+/// - shaping (bool): whether to apply OpenType shaping
+/// - kerning (bool): whether to apply kerning
+/// - features (array, dictionary): OpenType features, see `measure-text`
+/// - direction (auto, direction): text direction, guessed from `text` if `auto`
+/// - script (auto, str): ISO 15924 script tag, guessed from `text` if `auto`
+/// - lang (auto, str): BCP-47 language tag
+/// -> dictionary, none
+#let text-to-svg(
+  data,
+  text,
+  index: 0,
+  size: 11pt,
+  fill: black,
+  shaping: true,
+  kerning: true,
+  features: (),
+  direction: auto,
+  script: auto,
+  lang: auto,
+) = {
+  assert(
+    type(index) == int and 0 <= index and index < 0xFFFFFFFF,
+    message: "index must be an integer between 0 and 2^32 - 1",
+  )
+  assert(type(text) == str, message: "`text` must be a string.")
+  cbor(melt.text_to_svg(
+    data,
+    cbor.encode(index),
+    cbor.encode(text),
+    cbor.encode((
+      shaping: _into_shaping_options(
+        size,
+        shaping,
+        kerning,
+        features,
+        direction,
+        script,
+        lang,
+      ),
+      fill: _into_svg_paint(fill),
+    )),
+  ))
+}